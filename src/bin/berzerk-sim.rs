@@ -0,0 +1,58 @@
+//! plays batches of games with a bot, without a window or sound, and prints statistics about them
+//! `berzerk-sim --bot scripted --seed 1 --games 1000 --difficulty normal --mode campaign --format csv`
+//! `--replays DIR` also saves every game as DIR/seed-N.json, which `berzerk --render-replay` can turn into a GIF
+
+extern crate berzerk;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use berzerk::campaign::Campaign;
+use berzerk::simulation::{self, Options, Report};
+
+/// how often progress is written to stderr, in games
+const PROGRESS_EVERY: u32 = 100;
+
+/// the report goes to stdout and everything else to stderr so the output can be piped straight into a file
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = Options::parse(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(2)
+    });
+    let campaign = match options.campaign {
+        Some(ref path) => Campaign::load(Path::new(path)).unwrap_or_else(|e| {
+            eprintln!("could not load campaign: {}", e);
+            process::exit(1)
+        }),
+        None => Campaign::default(),
+    };
+
+    if let Some(ref dir) = options.replays {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("could not make the replay folder {}: {}", dir, e);
+            process::exit(1)
+        }
+    }
+
+    let mut report = Report::default();
+    simulation::run(&options, campaign, |record| {
+        report.add(record);
+        if let Some(ref dir) = options.replays {
+            let path = Path::new(dir).join(format!("seed-{}.json", record.seed));
+            if let Err(e) = record.replay(&options).save(&path) {
+                eprintln!("could not save the replay: {}", e);
+            }
+        }
+        if report.games % PROGRESS_EVERY == 0 {
+            eprintln!("played {} of {} games", report.games, options.games);
+        }
+    });
+    if options.json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report.to_csv());
+    }
+}
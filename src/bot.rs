@@ -0,0 +1,280 @@
+use environment::{Action, Observation};
+use game::{Direction, LOGICAL_SIZE};
+use models::bullet::BULLET_SIZE;
+use models::enemy::ENEMY_SIZE;
+use models::player::{PLAYER_X_SIZE, PLAYER_Y_SIZE};
+use net::PlayerState;
+
+/// how far ahead the bot looks for walls and robots before taking a step
+const LOOKAHEAD: f64 = 10.0;
+/// room kept between the bot and a robot it walks past
+const ROBOT_MARGIN: f64 = 10.0;
+/// how close a robot's bullet can get before the bot steps out of its way
+const DANGER_RANGE: f64 = 200.0;
+/// how far to the side of a bullet's path the bot still counts as in the way
+const DANGER_WIDTH: f64 = 25.0;
+/// how far a robot can be from the line of fire and still be hit
+const AIM_TOLERANCE: f64 = 18.0;
+/// updates between shots
+const RELOAD: u32 = 20;
+/// updates spent walking around something in the way before heading straight for the target again
+const DETOUR: u32 = 25;
+/// the bot stops closing in on an axis once it is this close
+const CLOSE_ENOUGH: f64 = 5.0;
+
+/// a scripted player that picks an action from what it can see, like a person at the keyboard would
+/// in order it steps out of the way of robot bullets, shoots robots lined up with it,
+/// and otherwise walks to the exit, or to the nearest robot when the exit is closed
+/// reload: updates before it shoots again
+/// detour: the direction it is walking around something in and for how many more updates
+#[derive(Default)]
+pub struct Bot {
+    reload: u32,
+    detour: Option<(Direction, u32)>,
+}
+
+/// [x0, y0, x1, y1] of the player's collision box moved by dx, dy
+fn player_box(player: &PlayerState, dx: f64, dy: f64) -> [f64; 4] {
+    [player.x + dx, player.y + dy, player.x + dx + PLAYER_X_SIZE, player.y + dy + PLAYER_Y_SIZE]
+}
+
+/// true when two boxes touch, counting edges the same way the game's collisions do
+fn overlaps(a: &[f64; 4], b: &[f64; 4]) -> bool {
+    a[2] >= b[0] && b[2] >= a[0] && a[3] >= b[1] && b[3] >= a[1]
+}
+
+fn step(dir: Direction, distance: f64) -> (f64, f64) {
+    match dir {
+        Direction::WEST => (-distance, 0.0),
+        Direction::NORTH => (0.0, -distance),
+        Direction::EAST => (distance, 0.0),
+        Direction::SOUTH => (0.0, distance),
+    }
+}
+
+fn turns(dir: Direction) -> [Direction; 3] {
+    match dir {
+        Direction::WEST | Direction::EAST => [Direction::NORTH, Direction::SOUTH, opposite(dir)],
+        Direction::NORTH | Direction::SOUTH => [Direction::EAST, Direction::WEST, opposite(dir)],
+    }
+}
+
+fn opposite(dir: Direction) -> Direction {
+    match dir {
+        Direction::WEST => Direction::EAST,
+        Direction::NORTH => Direction::SOUTH,
+        Direction::EAST => Direction::WEST,
+        Direction::SOUTH => Direction::NORTH,
+    }
+}
+
+/// the point in the opening at the top the bot walks to
+fn exit_point() -> (f64, f64) {
+    (LOGICAL_SIZE[0] / 2.0 - PLAYER_X_SIZE / 2.0, 10.0)
+}
+
+impl Bot {
+    pub fn new() -> Self {
+        Bot {
+            reload: 0,
+            detour: None,
+        }
+    }
+
+    /// the action for the first player in the observation, standing still once they are out
+    pub fn act(&mut self, observation: &Observation) -> Action {
+        let player = match observation.players.first() {
+            Some(player) if player.health > 0 => *player,
+            _ => return Action::default(),
+        };
+        if self.reload > 0 {
+            self.reload -= 1;
+        }
+
+        if let Some(dir) = self.dodge(&player, observation) {
+            return Action { dir: Some(dir), fire: false }
+        }
+
+        if let Some(dir) = self.aim(&player, observation) {
+            // a step turns the player to face the robot, the shot goes next update
+            if player.dir != dir {
+                return Action { dir: Some(dir), fire: false }
+            }
+            if self.reload == 0 {
+                self.reload = RELOAD;
+                return Action { dir: None, fire: true }
+            }
+            return Action::default()
+        }
+
+        let target = self.target(&player, observation);
+        Action { dir: self.walk(&player, target, observation), fire: false }
+    }
+
+    /// true when a step in the direction would run into a wall or a robot
+    fn blocked(&self, player: &PlayerState, dir: Direction, observation: &Observation) -> bool {
+        let (dx, dy) = step(dir, LOOKAHEAD);
+        let next = player_box(player, dx, dy);
+        observation.walls.iter().any(|wall| overlaps(&next, wall)) ||
+            observation.enemies.iter().any(|enemy| {
+                let robot = [enemy.x - ROBOT_MARGIN, enemy.y - ROBOT_MARGIN,
+                    enemy.x + ENEMY_SIZE + ROBOT_MARGIN, enemy.y + ENEMY_SIZE + ROBOT_MARGIN];
+                overlaps(&next, &robot)
+            })
+    }
+
+    /// a sideways step out of the path of the closest robot bullet heading for the player
+    fn dodge(&self, player: &PlayerState, observation: &Observation) -> Option<Direction> {
+        let incoming = observation.bullets.iter()
+            .filter(|bullet| bullet.owner.is_none())
+            .filter_map(|bullet| {
+                let (ahead, side) = match bullet.dir {
+                    Direction::EAST => (player.x - bullet.x, bullet.y - player.y),
+                    Direction::WEST => (bullet.x - player.x, bullet.y - player.y),
+                    Direction::SOUTH => (player.y - bullet.y, bullet.x - player.x),
+                    Direction::NORTH => (bullet.y - player.y, bullet.x - player.x),
+                };
+                if (0.0..DANGER_RANGE).contains(&ahead) && side.abs() < DANGER_WIDTH {
+                    Some((ahead, side, bullet.dir))
+                } else {
+                    None
+                }
+            })
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(::std::cmp::Ordering::Equal));
+        let (_, side, dir) = incoming?;
+        // away from the side the bullet is on first
+        let (away, toward) = match dir {
+            Direction::EAST | Direction::WEST => if side >= 0.0 { (Direction::NORTH, Direction::SOUTH) } else { (Direction::SOUTH, Direction::NORTH) },
+            _ => if side >= 0.0 { (Direction::WEST, Direction::EAST) } else { (Direction::EAST, Direction::WEST) },
+        };
+        [away, toward].iter().cloned().find(|&dir| !self.blocked(player, dir, observation))
+    }
+
+    /// the direction to shoot in when a robot is lined up with no wall in between, the nearest one first
+    fn aim(&self, player: &PlayerState, observation: &Observation) -> Option<Direction> {
+        observation.enemies.iter()
+            .filter_map(|enemy| {
+                let (dx, dy) = (enemy.x - player.x, enemy.y - player.y);
+                let (dir, distance) = if dy.abs() < AIM_TOLERANCE {
+                    (if dx > 0.0 { Direction::EAST } else { Direction::WEST }, dx.abs())
+                } else if dx.abs() < AIM_TOLERANCE {
+                    (if dy > 0.0 { Direction::SOUTH } else { Direction::NORTH }, dy.abs())
+                } else {
+                    return None
+                };
+                // the box the bullet sweeps on its way to the robot
+                let path = [player.x.min(enemy.x), player.y.min(enemy.y),
+                    player.x.max(enemy.x) + BULLET_SIZE, player.y.max(enemy.y) + BULLET_SIZE];
+                let path = match dir {
+                    Direction::EAST | Direction::WEST => [path[0], player.y, path[2], player.y + BULLET_SIZE],
+                    _ => [player.x, path[1], player.x + BULLET_SIZE, path[3]],
+                };
+                if observation.walls.iter().any(|wall| overlaps(&path, wall)) {
+                    None
+                } else {
+                    Some((distance, dir))
+                }
+            })
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(::std::cmp::Ordering::Equal))
+            .map(|(_, dir)| dir)
+    }
+
+    /// the exit when it is open, otherwise the nearest robot
+    fn target(&self, player: &PlayerState, observation: &Observation) -> (f64, f64) {
+        let exit = exit_point();
+        let door = [exit.0, exit.1, exit.0 + PLAYER_X_SIZE, exit.1 + PLAYER_Y_SIZE];
+        let exit_open = !observation.walls.iter().any(|wall| overlaps(&door, wall));
+        let distance = |x: f64, y: f64| (x - player.x).powi(2) + (y - player.y).powi(2);
+        match observation.enemies.iter().min_by(|a, b| {
+            distance(a.x, a.y).partial_cmp(&distance(b.x, b.y)).unwrap_or(::std::cmp::Ordering::Equal)
+        }) {
+            Some(enemy) if !exit_open => (enemy.x, enemy.y),
+            _ => exit,
+        }
+    }
+
+    /// a step toward the target, closing the longer distance first
+    /// when both ways are blocked it walks around whatever is in the way for a while
+    fn walk(&mut self, player: &PlayerState, target: (f64, f64), observation: &Observation) -> Option<Direction> {
+        if let Some((dir, left)) = self.detour {
+            if left > 0 && !self.blocked(player, dir, observation) {
+                self.detour = Some((dir, left - 1));
+                return Some(dir)
+            }
+            self.detour = None;
+        }
+
+        let (dx, dy) = (target.0 - player.x, target.1 - player.y);
+        let horizontal = (if dx > 0.0 { Direction::EAST } else { Direction::WEST }, dx.abs());
+        let vertical = (if dy > 0.0 { Direction::SOUTH } else { Direction::NORTH }, dy.abs());
+        let (first, second) = if dx.abs() > dy.abs() { (horizontal, vertical) } else { (vertical, horizontal) };
+        for &(dir, distance) in [first, second].iter() {
+            if distance >= CLOSE_ENOUGH && !self.blocked(player, dir, observation) {
+                return Some(dir)
+            }
+        }
+        if first.1 < CLOSE_ENOUGH {
+            return None
+        }
+
+        let around = turns(first.0).iter().cloned().find(|&dir| !self.blocked(player, dir, observation));
+        if let Some(dir) = around {
+            self.detour = Some((dir, DETOUR));
+        }
+        around
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use models::enemy::EnemyKind;
+    use net::{BulletState, EnemyState};
+
+    fn player(x: f64, y: f64, dir: Direction) -> PlayerState {
+        PlayerState { x, y, dir, health: 3, max_health: 3, is_moving: false, score: 0, kills: 0 }
+    }
+
+    fn room(player: PlayerState) -> Observation {
+        Observation { players: vec![player], enemies: Vec::new(), bullets: Vec::new(), walls: Vec::new(), score: 0, level: 1 }
+    }
+
+    #[test]
+    fn test_shoots_lined_up_robot() {
+        let mut observation = room(player(100.0, 300.0, Direction::NORTH));
+        observation.enemies.push(EnemyState { x: 400.0, y: 305.0, dir: Direction::WEST, kind: EnemyKind::Soldier });
+        let mut bot = Bot::new();
+        assert!(bot.act(&observation) == Action { dir: Some(Direction::EAST), fire: false });
+        observation.players[0].dir = Direction::EAST;
+        assert!(bot.act(&observation) == Action { dir: None, fire: true });
+        // a wall in between saves the robot
+        observation.walls.push([200.0, 250.0, 225.0, 350.0]);
+        assert!(!bot.act(&observation).fire);
+    }
+
+    #[test]
+    fn test_dodges_robot_bullet() {
+        let mut observation = room(player(300.0, 300.0, Direction::EAST));
+        observation.bullets.push(BulletState { owner: None, x: 200.0, y: 305.0, dir: Direction::EAST, speed: 5.0 });
+        let action = Bot::new().act(&observation);
+        assert!(action.dir == Some(Direction::NORTH));
+        // bullets moving away or shot by a player are ignored
+        observation.bullets[0].dir = Direction::WEST;
+        assert!(Bot::new().dodge(&observation.players[0], &observation).is_none());
+        observation.bullets[0] = BulletState { owner: Some(0), x: 200.0, y: 305.0, dir: Direction::EAST, speed: 5.0 };
+        assert!(Bot::new().dodge(&observation.players[0], &observation).is_none());
+    }
+
+    #[test]
+    fn test_walks_around_wall_to_exit() {
+        let mut observation = room(player(90.0, 300.0, Direction::EAST));
+        observation.walls.push([120.0, 200.0, 145.0, 400.0]);
+        let mut bot = Bot::new();
+        // the exit is up and to the right, the wall is in the way of going right
+        assert!(bot.act(&observation).dir == Some(Direction::NORTH));
+        observation.players[0].x = exit_point().0;
+        observation.players[0].y = 100.0;
+        assert!(bot.act(&observation).dir == Some(Direction::NORTH));
+    }
+
+}
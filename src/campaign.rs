@@ -0,0 +1,295 @@
+extern crate rand;
+
+use std::fs;
+use std::path::Path;
+
+use self::rand::Rng;
+use difficulty::LevelParams;
+use models::enemy::EnemyKind;
+
+/// the robots placed in a room
+/// Difficulty: the count and mix come from the difficulty preset for the room's level
+/// Fixed: exactly this many of each kind
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Robots {
+    Difficulty,
+    Fixed { drones: u32, soldiers: u32, hunters: u32 },
+}
+
+/// what finishes a room
+/// Top: walking out through the opening at the top, robots left or not
+/// Cleared: destroying every robot, there is no need to find the exit
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Exit {
+    Top,
+    Cleared,
+}
+
+/// what has to happen to win the campaign
+/// Rooms: finish this many rooms
+/// Score: reach this score
+/// Time: stay alive for this many seconds of play
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WinCondition {
+    Rooms(u32),
+    Score(u32),
+    Time(f64),
+}
+
+/// one room of a campaign
+/// intermission: shown under the level banner before the room starts, empty for none
+#[derive(Clone, PartialEq, Debug)]
+pub struct Room {
+    pub robots: Robots,
+    pub exit: Exit,
+    pub intermission: String,
+}
+
+/// how far the player has got, checked against the win condition
+/// rooms_finished: rooms left through the exit or cleared
+/// time: seconds spent playing
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Progress {
+    pub rooms_finished: u32,
+    pub score: u32,
+    pub time: f64,
+}
+
+/// the rooms of a campaign in order and how it is won
+/// rooms past the end of the list play the last room again, so a campaign
+/// that ends on a `robots = difficulty` room keeps getting harder
+/// the file has a [campaign] section followed by one [room] section per room,
+/// with one `name = value` per line, # starts a comment
+#[derive(Clone, PartialEq, Debug)]
+pub struct Campaign {
+    pub name: String,
+    pub win: WinCondition,
+    pub rooms: Vec<Room>,
+}
+
+impl Default for Room {
+    fn default() -> Self {
+        Room {
+            robots: Robots::Difficulty,
+            exit: Exit::Top,
+            intermission: String::new(),
+        }
+    }
+}
+
+/// the original game, four rooms from the difficulty preset left through the top
+impl Default for Campaign {
+    fn default() -> Self {
+        Campaign {
+            name: "classic".to_string(),
+            win: WinCondition::Rooms(4),
+            rooms: vec![Room::default()],
+        }
+    }
+}
+
+impl Robots {
+    /// the kind of every robot to place in the room
+    pub fn kinds<R: Rng>(&self, params: &LevelParams, rng: &mut R) -> Vec<EnemyKind> {
+        match *self {
+            Robots::Difficulty => (0..params.robot_count).map(|_| params.pick_kind(rng)).collect(),
+            Robots::Fixed { drones, soldiers, hunters } => {
+                let mut kinds = Vec::new();
+                kinds.extend((0..drones).map(|_| EnemyKind::Drone));
+                kinds.extend((0..soldiers).map(|_| EnemyKind::Soldier));
+                kinds.extend((0..hunters).map(|_| EnemyKind::Hunter));
+                kinds
+            },
+        }
+    }
+}
+
+impl WinCondition {
+    /// reads `rooms N`, `score N` or `time SECONDS`
+    fn parse(value: &str) -> Option<WinCondition> {
+        let mut parts = value.split_whitespace();
+        let condition = match (parts.next(), parts.next().map(|amount| amount.parse::<f64>())) {
+            (Some("rooms"), Some(Ok(n))) if n >= 1.0 => WinCondition::Rooms(n as u32),
+            (Some("score"), Some(Ok(n))) if n >= 1.0 => WinCondition::Score(n as u32),
+            (Some("time"), Some(Ok(n))) if n > 0.0 => WinCondition::Time(n),
+            _ => return None,
+        };
+        if parts.next().is_some() {
+            return None
+        }
+        Some(condition)
+    }
+}
+
+impl Campaign {
+    /// reads a campaign from a file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Campaign::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// reads the campaign file format
+    /// unlike the settings file any line that does not make sense is an error,
+    /// so a mistake in a campaign is reported instead of quietly changing it
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut campaign = Campaign::default();
+        campaign.rooms.clear();
+        let mut section = String::new();
+        for (number, line) in contents.lines().enumerate() {
+            let number = number + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line[1..line.len() - 1].trim().to_string();
+                match section.as_str() {
+                    "campaign" => (),
+                    "room" => campaign.rooms.push(Room::default()),
+                    _ => return Err(format!("line {}: unknown section [{}]", number, section)),
+                }
+                continue
+            }
+            let mut parts = line.splitn(2, '=');
+            let (name, value) = match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) => (name.trim(), value.trim()),
+                _ => return Err(format!("line {}: expected name = value", number)),
+            };
+            let bad_value = || format!("line {}: bad value for {}: {}", number, name, value);
+            match (section.as_str(), campaign.rooms.last_mut()) {
+                ("campaign", _) => match name {
+                    "name" => campaign.name = value.to_string(),
+                    "win" => campaign.win = WinCondition::parse(value).ok_or_else(bad_value)?,
+                    _ => return Err(format!("line {}: unknown name {}", number, name)),
+                },
+                ("room", Some(room)) => match name {
+                    "robots" if value == "difficulty" => room.robots = Robots::Difficulty,
+                    "drones" | "soldiers" | "hunters" => {
+                        let count: u32 = value.parse().map_err(|_| bad_value())?;
+                        let (mut drones, mut soldiers, mut hunters) = match room.robots {
+                            Robots::Fixed { drones, soldiers, hunters } => (drones, soldiers, hunters),
+                            Robots::Difficulty => (0, 0, 0),
+                        };
+                        match name {
+                            "drones" => drones = count,
+                            "soldiers" => soldiers = count,
+                            _ => hunters = count,
+                        }
+                        room.robots = Robots::Fixed { drones, soldiers, hunters };
+                    },
+                    "exit" => room.exit = match value {
+                        "top" => Exit::Top,
+                        "cleared" => Exit::Cleared,
+                        _ => return Err(bad_value()),
+                    },
+                    "intermission" => room.intermission = value.to_string(),
+                    _ => return Err(format!("line {}: unknown name {}", number, name)),
+                },
+                _ => return Err(format!("line {}: {} is not in a section", number, name)),
+            }
+        }
+        if campaign.rooms.is_empty() {
+            return Err("the campaign has no rooms".to_string())
+        }
+        for (i, room) in campaign.rooms.iter().enumerate() {
+            if room.robots == (Robots::Fixed { drones: 0, soldiers: 0, hunters: 0 }) && room.exit == Exit::Cleared {
+                return Err(format!("room {} has no robots to clear", i + 1))
+            }
+        }
+        Ok(campaign)
+    }
+
+    /// the room played on a level, starting from 1
+    pub fn room(&self, level: u32) -> &Room {
+        let index = (level.max(1) - 1) as usize;
+        &self.rooms[index.min(self.rooms.len() - 1)]
+    }
+
+    pub fn is_won(&self, progress: &Progress) -> bool {
+        match self.win {
+            WinCondition::Rooms(rooms) => progress.rooms_finished >= rooms,
+            WinCondition::Score(score) => progress.score >= score,
+            WinCondition::Time(time) => progress.time >= time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use difficulty::Difficulty;
+
+    fn progress(rooms_finished: u32, score: u32, time: f64) -> Progress {
+        Progress { rooms_finished, score, time }
+    }
+
+    #[test]
+    fn test_classic_file_matches_default() {
+        let classic = Campaign::parse(include_str!("../assets/campaigns/classic.cfg"));
+        assert!(classic == Ok(Campaign::default()));
+    }
+
+    #[test]
+    fn test_parse_rooms() {
+        let campaign = Campaign::parse("
+            [campaign]
+            name = test
+            win = score 5000
+            [room]
+            intermission = GET READY
+            drones = 2
+            hunters = 1
+            exit = cleared
+            [room]
+            robots = difficulty
+        ").unwrap();
+        assert!(campaign.name == "test");
+        assert!(campaign.win == WinCondition::Score(5000));
+        assert!(campaign.rooms.len() == 2);
+        assert!(campaign.rooms[0].robots == Robots::Fixed { drones: 2, soldiers: 0, hunters: 1 });
+        assert!(campaign.rooms[0].exit == Exit::Cleared);
+        assert!(campaign.rooms[0].intermission == "GET READY");
+        assert!(campaign.rooms[1] == Room::default());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Campaign::parse("[campaign]\nwin = rooms 3\n").is_err());
+        assert!(Campaign::parse("[room]\nexit = sideways\n").is_err());
+        assert!(Campaign::parse("[room]\nrobots = lots\n").is_err());
+        assert!(Campaign::parse("[campaign]\nwin = rooms\n[room]\n").is_err());
+        assert!(Campaign::parse("[rooms]\n").is_err());
+        assert!(Campaign::parse("name = test\n[room]\n").is_err());
+        assert!(Campaign::parse("[room]\ndrones = 0\nexit = cleared\n").is_err());
+    }
+
+    #[test]
+    fn test_last_room_repeats() {
+        let campaign = Campaign::parse("[room]\nsoldiers = 1\n[room]\nsoldiers = 2\n").unwrap();
+        assert!(campaign.room(1).robots == Robots::Fixed { drones: 0, soldiers: 1, hunters: 0 });
+        assert!(campaign.room(2).robots == Robots::Fixed { drones: 0, soldiers: 2, hunters: 0 });
+        assert!(campaign.room(50) == campaign.room(2));
+    }
+
+    #[test]
+    fn test_win_conditions() {
+        let mut campaign = Campaign::default();
+        assert!(!campaign.is_won(&progress(3, 0, 0.0)));
+        assert!(campaign.is_won(&progress(4, 0, 0.0)));
+        campaign.win = WinCondition::Score(1000);
+        assert!(!campaign.is_won(&progress(10, 999, 0.0)));
+        assert!(campaign.is_won(&progress(0, 1000, 0.0)));
+        campaign.win = WinCondition::Time(60.0);
+        assert!(campaign.is_won(&progress(0, 0, 60.0)));
+    }
+
+    #[test]
+    fn test_fixed_robot_kinds() {
+        let params = Difficulty::Normal.level(1);
+        let robots = Robots::Fixed { drones: 1, soldiers: 2, hunters: 3 };
+        let kinds = robots.kinds(&params, &mut rand::thread_rng());
+        assert!(kinds.len() == 6);
+        assert!(kinds.iter().filter(|&&kind| kind == EnemyKind::Hunter).count() == 3);
+        assert!(Robots::Difficulty.kinds(&params, &mut rand::thread_rng()).len() == params.robot_count as usize);
+    }
+}
@@ -0,0 +1,38 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// the folder the game keeps its files in inside the user's config directory
+/// %APPDATA%\berzerk on Windows, $XDG_CONFIG_HOME/berzerk or ~/.config/berzerk elsewhere
+/// falls back to the working directory when none of those are set
+pub fn config_dir() -> PathBuf {
+    let base = env::var_os("APPDATA").map(PathBuf::from)
+        .or_else(|| env::var_os("XDG_CONFIG_HOME").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("berzerk")
+}
+
+/// the full path of a file in the config directory
+pub fn config_path(file_name: &str) -> PathBuf {
+    config_dir().join(file_name)
+}
+
+/// writes a file next to the real one and then renames it over the top
+/// so a crash part way through never leaves a half written file
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)
+}
@@ -0,0 +1,333 @@
+extern crate piston;
+
+use piston::input::Key;
+use game::Direction;
+
+/// the things the player can do, keys are bound to these instead of being matched directly
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Restart,
+    Pause,
+}
+
+/// every action in the order they are listed in the file and on the controls screen
+pub const ACTIONS: [Action; 7] = [
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Fire,
+    Action::Restart,
+    Action::Pause,
+];
+
+/// keys that can be bound, names are written to the settings file as piston spells them
+pub const BINDABLE_KEYS: [Key; 65] = [
+    Key::Up, Key::Down, Key::Left, Key::Right,
+    Key::Space, Key::Return, Key::Tab, Key::Backspace,
+    Key::LShift, Key::RShift, Key::LCtrl, Key::RCtrl, Key::LAlt, Key::RAlt,
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I,
+    Key::J, Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R,
+    Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::D0, Key::D1, Key::D2, Key::D3, Key::D4, Key::D5, Key::D6, Key::D7, Key::D8, Key::D9,
+    Key::NumPad0, Key::NumPad1, Key::NumPad2, Key::NumPad3, Key::NumPad4,
+    Key::NumPad5, Key::NumPad6, Key::NumPad7, Key::NumPad8, Key::NumPad9,
+    Key::Comma, Key::Period, Key::Slash, Key::Minus, Key::Equals,
+];
+
+impl Action {
+    /// the name used for the action in the settings file
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Fire => "fire",
+            Action::Restart => "restart",
+            Action::Pause => "pause",
+        }
+    }
+
+    /// the name shown for the action on the controls screen
+    pub fn label(&self) -> &'static str {
+        match *self {
+            Action::MoveUp => "MOVE UP",
+            Action::MoveDown => "MOVE DOWN",
+            Action::MoveLeft => "MOVE LEFT",
+            Action::MoveRight => "MOVE RIGHT",
+            Action::Fire => "FIRE",
+            Action::Restart => "RESTART",
+            Action::Pause => "PAUSE",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().cloned().find(|action| action.name() == name)
+    }
+
+    /// the direction a movement action moves the player in
+    pub fn direction(&self) -> Option<Direction> {
+        match *self {
+            Action::MoveUp => Some(Direction::NORTH),
+            Action::MoveDown => Some(Direction::SOUTH),
+            Action::MoveLeft => Some(Direction::WEST),
+            Action::MoveRight => Some(Direction::EAST),
+            _ => None,
+        }
+    }
+}
+
+/// the name of a key as it is written in the settings file
+pub fn key_name(key: Key) -> String {
+    format!("{:?}", key)
+}
+
+/// looks up a bindable key by the name it is written with
+pub fn key_from_name(name: &str) -> Option<Key> {
+    BINDABLE_KEYS.iter().cloned().find(|key| key_name(*key) == name)
+}
+
+/// maps keys to actions, an action can have several keys but a key only triggers one action
+/// stored in the [controls] section of the settings file
+/// one action per line, `move_up = Up, W`, with # starting a comment
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bindings {
+    keys: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for Bindings {
+    /// arrow keys to move, space to shoot, R to restart and P to pause
+    fn default() -> Self {
+        Bindings {
+            keys: vec![
+                (Action::MoveUp, vec![Key::Up]),
+                (Action::MoveDown, vec![Key::Down]),
+                (Action::MoveLeft, vec![Key::Left]),
+                (Action::MoveRight, vec![Key::Right]),
+                (Action::Fire, vec![Key::Space]),
+                (Action::Restart, vec![Key::R]),
+                (Action::Pause, vec![Key::P]),
+            ],
+        }
+    }
+}
+
+impl Bindings {
+    /// W A S D to move and left shift to shoot, restart and pause are left to the first player
+    pub fn second_player() -> Self {
+        Bindings {
+            keys: vec![
+                (Action::MoveUp, vec![Key::W]),
+                (Action::MoveDown, vec![Key::S]),
+                (Action::MoveLeft, vec![Key::A]),
+                (Action::MoveRight, vec![Key::D]),
+                (Action::Fire, vec![Key::LShift]),
+                (Action::Restart, vec![]),
+                (Action::Pause, vec![]),
+            ],
+        }
+    }
+
+    /// reads bindings written one action per line
+    /// actions that are not listed keep their default keys
+    /// lines that do not name an action or a bindable key are skipped
+    pub fn parse(contents: &str) -> Self {
+        Bindings::parse_with_defaults(contents, Bindings::default())
+    }
+
+    /// reads bindings like parse, with actions that are not listed keeping the keys in defaults
+    pub fn parse_with_defaults(contents: &str, defaults: Bindings) -> Self {
+        let mut bindings = defaults;
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut parts = line.splitn(2, '=');
+            let (name, keys) = match (parts.next(), parts.next()) {
+                (Some(name), Some(keys)) => (name.trim(), keys),
+                _ => continue,
+            };
+            if let Some(action) = Action::from_name(name) {
+                bindings.clear(action);
+                for key in keys.split(',').filter_map(|key| key_from_name(key.trim())) {
+                    bindings.bind(action, key);
+                }
+            }
+        }
+        bindings
+    }
+
+    /// the bindings one action per line with the keys separated by commas
+    pub fn to_file_string(&self) -> String {
+        let mut contents = String::new();
+        for &(action, ref keys) in &self.keys {
+            let names: Vec<String> = keys.iter().map(|key| key_name(*key)).collect();
+            contents.push_str(&format!("{} = {}\n", action.name(), names.join(", ")));
+        }
+        contents
+    }
+
+    /// the action a key is bound to
+    pub fn action_for(&self, key: Key) -> Option<Action> {
+        self.keys.iter()
+            .find(|&(_, keys)| keys.contains(&key))
+            .map(|&(action, _)| action)
+    }
+
+    /// the keys bound to an action
+    pub fn keys_for(&self, action: Action) -> &[Key] {
+        self.keys.iter()
+            .find(|&&(bound, _)| bound == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// adds a key to an action, taking it away from any action it was bound to before
+    pub fn bind(&mut self, action: Action, key: Key) {
+        self.unbind(key);
+        for &mut (bound, ref mut keys) in &mut self.keys {
+            if bound == action {
+                keys.push(key);
+            }
+        }
+    }
+
+    /// takes a key away from whichever action it is bound to
+    pub fn unbind(&mut self, key: Key) {
+        for &mut (_, ref mut keys) in &mut self.keys {
+            keys.retain(|bound| *bound != key);
+        }
+    }
+
+    /// removes every key from an action
+    pub fn clear(&mut self, action: Action) {
+        for &mut (bound, ref mut keys) in &mut self.keys {
+            if bound == action {
+                keys.clear();
+            }
+        }
+    }
+
+    /// the text shown for an action on the controls screen, e.g. `MOVE UP: Up, W`
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<String> = self.keys_for(action).iter().map(|key| key_name(*key)).collect();
+        format!("{}: {}", action.label(), names.join(", "))
+    }
+}
+
+/// the movement keys that are held down in the order they were pressed
+/// the player moves in the direction of the most recent one so releasing it
+/// goes back to the key that was held before
+#[derive(Default)]
+pub struct HeldKeys {
+    held: Vec<(Key, Direction)>,
+}
+
+impl HeldKeys {
+    /// a key repeating while held keeps its place in the order
+    pub fn press(&mut self, key: Key, dir: Direction) {
+        if !self.held.iter().any(|&(held, _)| held == key) {
+            self.held.push((key, dir));
+        }
+    }
+
+    pub fn release(&mut self, key: Key) {
+        self.held.retain(|&(held, _)| held != key);
+    }
+
+    /// forgets every key, used when releases can be missed like when the window loses focus
+    pub fn clear(&mut self) {
+        self.held.clear();
+    }
+
+    /// the direction of the most recently pressed key that is still held
+    pub fn direction(&self) -> Option<Direction> {
+        self.held.last().map(|&(_, dir)| dir)
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_default_bindings() {
+        let bindings = Bindings::default();
+        assert!(bindings.action_for(Key::Up) == Some(Action::MoveUp));
+        assert!(bindings.action_for(Key::Space) == Some(Action::Fire));
+        assert!(bindings.action_for(Key::Q).is_none());
+    }
+
+    #[test]
+    fn test_bind_moves_key() {
+        let mut bindings = Bindings::default();
+        bindings.bind(Action::MoveUp, Key::W);
+        bindings.bind(Action::Fire, Key::Up);
+        assert!(bindings.keys_for(Action::MoveUp) == [Key::W]);
+        assert!(bindings.keys_for(Action::Fire) == [Key::Space, Key::Up]);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let mut bindings = Bindings::default();
+        bindings.bind(Action::MoveLeft, Key::A);
+        bindings.clear(Action::Pause);
+        bindings.bind(Action::Pause, Key::Tab);
+        assert!(Bindings::parse(&bindings.to_file_string()) == bindings);
+    }
+
+    #[test]
+    fn test_parse_skips_unknown() {
+        let bindings = Bindings::parse("jump = Space\nfire = NotAKey, F # comment\n");
+        assert!(bindings.keys_for(Action::Fire) == [Key::F]);
+        assert!(bindings.keys_for(Action::MoveUp) == [Key::Up]);
+    }
+
+    #[test]
+    fn test_every_numpad_key_binds() {
+        let bindings = Bindings::parse("fire = NumPad3, NumPad7, NumPad9\n");
+        assert!(bindings.keys_for(Action::Fire) == [Key::NumPad3, Key::NumPad7, Key::NumPad9]);
+    }
+
+    #[test]
+    fn test_second_player_defaults() {
+        let bindings = Bindings::parse_with_defaults("fire = RCtrl\n", Bindings::second_player());
+        assert!(bindings.keys_for(Action::MoveUp) == [Key::W]);
+        assert!(bindings.keys_for(Action::Fire) == [Key::RCtrl]);
+        assert!(bindings.action_for(Key::Up).is_none());
+    }
+
+    #[test]
+    fn test_held_keys_resume_previous() {
+        let mut held = HeldKeys::default();
+        held.press(Key::Right, Direction::EAST);
+        held.press(Key::Up, Direction::NORTH);
+        assert!(held.direction() == Some(Direction::NORTH));
+        held.release(Key::Up);
+        assert!(held.direction() == Some(Direction::EAST));
+        held.release(Key::Right);
+        assert!(held.direction().is_none());
+    }
+
+    #[test]
+    fn test_held_keys_release_older() {
+        let mut held = HeldKeys::default();
+        held.press(Key::Right, Direction::EAST);
+        held.press(Key::Up, Direction::NORTH);
+        held.release(Key::Right);
+        assert!(held.direction() == Some(Direction::NORTH));
+    }
+
+    #[test]
+    fn test_held_keys_repeat() {
+        let mut held = HeldKeys::default();
+        held.press(Key::Right, Direction::EAST);
+        held.press(Key::Up, Direction::NORTH);
+        held.press(Key::Right, Direction::EAST);
+        assert!(held.direction() == Some(Direction::NORTH));
+    }
+}
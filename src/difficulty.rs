@@ -0,0 +1,193 @@
+extern crate rand;
+
+use self::rand::Rng;
+use models::enemy::EnemyKind;
+
+/// the difficulty presets the player can pick from the menu
+/// Easy: fewer, slower robots that shoot less
+/// Normal: close to the original game for the first levels
+/// Hard: more robots that shoot more and move faster
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+/// a value that changes by step every level after the first and stops at limit
+/// start: the value on level 1
+/// step: added each level, can be negative
+/// limit: the value never goes past this in the direction of step
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Curve {
+    pub start: f64,
+    pub step: f64,
+    pub limit: f64,
+}
+
+impl Curve {
+    pub fn at(&self, level: u32) -> f64 {
+        let value = self.start + self.step * level.saturating_sub(1) as f64;
+        if self.step >= 0.0 {
+            value.min(self.limit)
+        } else {
+            value.max(self.limit)
+        }
+    }
+}
+
+/// how each part of the game scales with the level
+/// robot_count: robots placed in the room
+/// drone_share & hunter_share: fraction of the robots that are drones and hunters,
+/// the rest are soldiers
+/// fire_chance: chance each update that one of the robots shoots
+/// bullet_speed: how far robot bullets move each update
+/// robot_speed: how far a robot moves when it steps toward the player
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DifficultyModel {
+    pub robot_count: Curve,
+    pub drone_share: Curve,
+    pub hunter_share: Curve,
+    pub fire_chance: Curve,
+    pub bullet_speed: Curve,
+    pub robot_speed: Curve,
+}
+
+/// the values of a model worked out for one level
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct LevelParams {
+    pub robot_count: u32,
+    pub drone_share: f64,
+    pub hunter_share: f64,
+    pub fire_chance: f64,
+    pub bullet_speed: f64,
+    pub robot_speed: f64,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// the next difficulty, wrapping from hard back to easy
+    pub fn next(&self) -> Difficulty {
+        match *self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    /// the curves for this preset
+    pub fn model(&self) -> DifficultyModel {
+        match *self {
+            Difficulty::Easy => DifficultyModel {
+                robot_count: Curve { start: 3.0, step: 1.0, limit: 10.0 },
+                drone_share: Curve { start: 0.5, step: -0.05, limit: 0.2 },
+                hunter_share: Curve { start: 0.0, step: 0.03, limit: 0.2 },
+                fire_chance: Curve { start: 0.006, step: 0.0003, limit: 0.012 },
+                bullet_speed: Curve { start: 4.0, step: 0.1, limit: 6.0 },
+                robot_speed: Curve { start: 4.0, step: 0.1, limit: 6.0 },
+            },
+            Difficulty::Normal => DifficultyModel {
+                robot_count: Curve { start: 6.0, step: 2.0, limit: 16.0 },
+                drone_share: Curve { start: 0.2, step: -0.05, limit: 0.0 },
+                hunter_share: Curve { start: 0.0, step: 0.05, limit: 0.4 },
+                fire_chance: Curve { start: 0.0104, step: 0.0004, limit: 0.02 },
+                bullet_speed: Curve { start: 5.0, step: 0.2, limit: 8.0 },
+                robot_speed: Curve { start: 5.0, step: 0.2, limit: 8.0 },
+            },
+            Difficulty::Hard => DifficultyModel {
+                robot_count: Curve { start: 8.0, step: 2.0, limit: 20.0 },
+                drone_share: Curve { start: 0.0, step: 0.0, limit: 0.0 },
+                hunter_share: Curve { start: 0.2, step: 0.1, limit: 0.7 },
+                fire_chance: Curve { start: 0.015, step: 0.001, limit: 0.035 },
+                bullet_speed: Curve { start: 6.0, step: 0.3, limit: 10.0 },
+                robot_speed: Curve { start: 6.0, step: 0.3, limit: 10.0 },
+            },
+        }
+    }
+
+    /// the model for this preset worked out for a level
+    pub fn level(&self, level: u32) -> LevelParams {
+        self.model().level(level)
+    }
+}
+
+impl DifficultyModel {
+    pub fn level(&self, level: u32) -> LevelParams {
+        LevelParams {
+            robot_count: self.robot_count.at(level).round().max(1.0) as u32,
+            drone_share: self.drone_share.at(level).clamp(0.0, 1.0),
+            hunter_share: self.hunter_share.at(level).clamp(0.0, 1.0),
+            fire_chance: self.fire_chance.at(level).clamp(0.0, 1.0),
+            bullet_speed: self.bullet_speed.at(level),
+            robot_speed: self.robot_speed.at(level),
+        }
+    }
+}
+
+impl LevelParams {
+    /// picks the kind of the next robot using the level's mix
+    pub fn pick_kind<R: Rng>(&self, rng: &mut R) -> EnemyKind {
+        let roll: f64 = rng.gen();
+        if roll < self.drone_share {
+            EnemyKind::Drone
+        } else if roll < self.drone_share + self.hunter_share {
+            EnemyKind::Hunter
+        } else {
+            EnemyKind::Soldier
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    const PRESETS: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    #[test]
+    fn test_curve_caps() {
+        let rising = Curve { start: 4.0, step: 2.0, limit: 10.0 };
+        assert!(rising.at(1) == 4.0);
+        assert!(rising.at(3) == 8.0);
+        assert!(rising.at(100) == 10.0);
+        let falling = Curve { start: 0.5, step: -0.1, limit: 0.2 };
+        assert!(falling.at(100) == 0.2);
+    }
+
+    #[test]
+    fn test_high_levels_do_not_overflow() {
+        for preset in PRESETS.iter() {
+            let params = preset.level(u32::MAX);
+            assert!(params.robot_count >= 1);
+            assert!(params.fire_chance <= 1.0);
+            assert!(params.drone_share + params.hunter_share <= 1.0);
+        }
+    }
+
+    #[test]
+    fn test_presets_get_harder() {
+        for level in 1..20 {
+            let easy = Difficulty::Easy.level(level);
+            let normal = Difficulty::Normal.level(level);
+            let hard = Difficulty::Hard.level(level);
+            assert!(easy.robot_count <= normal.robot_count && normal.robot_count <= hard.robot_count);
+            assert!(easy.fire_chance <= normal.fire_chance && normal.fire_chance <= hard.fire_chance);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use campaign::Campaign;
+use game::{Direction, Game, Mode, State, FPS};
+use highscores::HighScores;
+use net::{BulletState, EnemyState, PlayerState};
+use render::software::{self, Image, SpriteImages};
+use settings::Settings;
+
+/// reward for every point scored
+const SCORE_REWARD: f64 = 1.0;
+/// reward for every room left, on top of any points
+const ROOM_REWARD: f64 = 500.0;
+/// taken away for every life lost
+const DEATH_PENALTY: f64 = 500.0;
+/// a step is one update of the game
+const STEP_TIME: f64 = 1.0 / FPS as f64;
+/// the number of actions from_index accepts, standing still or one of four directions, each with or without a shot
+pub const ACTION_COUNT: usize = 10;
+
+/// what the agent does on one step, the same as holding a movement key and pressing fire
+/// dir: the direction held, none to stand still
+/// fire: shoots once this step
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Action {
+    pub dir: Option<Direction>,
+    pub fire: bool,
+}
+
+/// everything the agent can see after a step
+/// players: the first player is the one the agent moves
+/// walls: [x0, y0, x1, y1] for opposite corners of each wall
+#[derive(Clone, PartialEq, Debug)]
+pub struct Observation {
+    pub players: Vec<PlayerState>,
+    pub enemies: Vec<EnemyState>,
+    pub bullets: Vec<BulletState>,
+    pub walls: Vec<[f64; 4]>,
+    pub score: u32,
+    pub level: u32,
+}
+
+/// the game with an agent moving the first player instead of the keyboard
+/// there is no window or sound and each step runs one update straight away,
+/// so games play as fast as they are stepped
+pub struct Environment {
+    game: Game,
+    mode: Mode,
+    score: u32,
+    level: u32,
+    health: Vec<u32>,
+}
+
+impl Action {
+    /// numbers the actions for agents that pick from a fixed list
+    /// 0 stands still, 1 to 4 move west, north, east and south, 5 to 9 are the same with a shot
+    pub fn from_index(index: usize) -> Action {
+        let dirs = [None, Some(Direction::WEST), Some(Direction::NORTH), Some(Direction::EAST), Some(Direction::SOUTH)];
+        Action {
+            dir: dirs[index % dirs.len()],
+            fire: index % ACTION_COUNT >= dirs.len(),
+        }
+    }
+
+    /// the number from_index turns back into this action
+    pub fn index(&self) -> usize {
+        let dir = match self.dir {
+            None => 0,
+            Some(Direction::WEST) => 1,
+            Some(Direction::NORTH) => 2,
+            Some(Direction::EAST) => 3,
+            Some(Direction::SOUTH) => 4,
+        };
+        if self.fire { dir + 5 } else { dir }
+    }
+}
+
+impl Observation {
+    /// what a game looks like right now
+    pub fn of(game: &Game) -> Observation {
+        let snapshot = game.observe();
+        Observation {
+            players: snapshot.players,
+            enemies: snapshot.enemies,
+            bullets: snapshot.bullets,
+            walls: game.walls.clone(),
+            score: snapshot.score,
+            level: snapshot.level,
+        }
+    }
+}
+
+impl Environment {
+    /// a one player game of the mode with the settings and campaign given, sound is turned off
+    pub fn new(settings: Settings, campaign: Campaign, mode: Mode) -> Self {
+        let mut game = Game::new(settings, HighScores::default(), campaign);
+        game.set_silent(true);
+        Environment {
+            game,
+            mode,
+            score: 0,
+            level: 1,
+            health: Vec::new(),
+        }
+    }
+
+    /// starts a new game, the same seed and the same actions always play out the same way
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game.start_seeded(self.mode, seed);
+        self.game.drive(None, false);
+        self.skip_transition();
+        let observation = self.observe();
+        self.remember(&observation);
+        observation
+    }
+
+    /// plays one update with the action, rewarded for points scored and rooms left
+    /// and penalised for lives lost, done is true once the game is won or lost
+    pub fn step(&mut self, action: Action) -> (Observation, f64, bool) {
+        if !self.game.is_over() {
+            self.game.drive(action.dir, action.fire);
+            self.game.tick(STEP_TIME);
+            self.skip_transition();
+        }
+        let observation = self.observe();
+        let reward = self.reward(&observation);
+        self.remember(&observation);
+        (observation, reward, self.game.is_over())
+    }
+
+    /// the game being played, for drawing it or looking at more than the observation has
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// draws the game as it is now with the software renderer
+    pub fn render(&mut self, sprites: &SpriteImages, width: u32, height: u32) -> Image {
+        software::render(&mut self.game, sprites, width, height)
+    }
+
+    /// the banner between rooms has nothing to act on so it is played through at once
+    fn skip_transition(&mut self) {
+        while let State::RoomTransition(_) = self.game.state() {
+            self.game.tick(STEP_TIME);
+        }
+    }
+
+    fn observe(&self) -> Observation {
+        Observation::of(&self.game)
+    }
+
+    /// lives only count when they go down, a new room filling them back up is not a reward
+    fn reward(&self, observation: &Observation) -> f64 {
+        let points = observation.score.saturating_sub(self.score) as f64;
+        let rooms = observation.level.saturating_sub(self.level) as f64;
+        let deaths: u32 = observation.players.iter().zip(&self.health)
+            .map(|(player, &health)| health.saturating_sub(player.health))
+            .sum();
+        points * SCORE_REWARD + rooms * ROOM_REWARD - deaths as f64 * DEATH_PENALTY
+    }
+
+    fn remember(&mut self, observation: &Observation) {
+        self.score = observation.score;
+        self.level = observation.level;
+        self.health = observation.players.iter().map(|player| player.health).collect();
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    fn environment() -> Environment {
+        Environment::new(Settings::default(), Campaign::default(), Mode::Campaign)
+    }
+
+    #[test]
+    fn test_reset_starts_playing() {
+        let mut env = environment();
+        let observation = env.reset(7);
+        assert!(env.game().state() == State::Playing);
+        assert!(observation.players.len() == 1);
+        assert!(observation.enemies.len() == 6);
+        assert!(observation.walls.len() == 9);
+        assert!(observation.score == 0 && observation.level == 1);
+    }
+
+    #[test]
+    fn test_same_seed_same_game() {
+        let actions: Vec<Action> = (0..300).map(|i| Action::from_index(i % ACTION_COUNT)).collect();
+        let play = |seed| {
+            let mut env = environment();
+            let mut last = env.reset(seed);
+            for action in &actions {
+                last = env.step(*action).0;
+            }
+            last
+        };
+        assert!(play(3) == play(3));
+    }
+
+    #[test]
+    fn test_action_indices() {
+        assert!(Action::from_index(0) == Action::default());
+        assert!(Action::from_index(2) == Action { dir: Some(Direction::NORTH), fire: false });
+        assert!(Action::from_index(9) == Action { dir: Some(Direction::SOUTH), fire: true });
+        assert!((0..ACTION_COUNT).all(|i| Action::from_index(i).index() == i));
+    }
+
+    #[test]
+    fn test_moving_player() {
+        let mut env = environment();
+        let start = env.reset(1).players[0];
+        let (observation, _, done) = env.step(Action { dir: Some(Direction::SOUTH), fire: false });
+        assert!(!done);
+        assert!(observation.players[0].y > start.y);
+    }
+}
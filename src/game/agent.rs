@@ -0,0 +1,82 @@
+extern crate rand;
+
+use piston::input::UpdateArgs;
+
+use self::rand::{SeedableRng, XorShiftRng};
+use models::player::Cause;
+use net::{Screen, Snapshot};
+
+use super::{Direction, Game, Mode, State};
+
+/// who steers the first player
+/// Keys: the keyboard, through the held keys
+/// Agent: a program, holding the direction it last asked for until it asks again
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Driver {
+    Keys,
+    Agent(Option<Direction>),
+}
+
+/// a random number generator that always gives the same numbers for the same seed
+/// the constant words keep the state from being all zeros, which xorshift can not start from
+pub fn seeded_rng(seed: u64) -> XorShiftRng {
+    XorShiftRng::from_seed([seed as u32, (seed >> 32) as u32, 0x9e37_79b9, 0x7f4a_7c15])
+}
+
+impl Game {
+    /// starts a new game of the given mode with the robots and their moves decided by the seed
+    pub fn start_seeded(&mut self, mode: Mode, seed: u64) {
+        self.rng = seeded_rng(seed);
+        self.start_game(mode);
+    }
+
+    /// hands the first player to a program until the game is dropped, the keyboard no longer moves them
+    /// dir is held until the next call and fire shoots once
+    pub fn drive(&mut self, dir: Option<Direction>, fire: bool) {
+        self.driver = Driver::Agent(dir);
+        if fire && self.state == State::Playing {
+            self.fire(0);
+        }
+    }
+
+    /// runs the game forward by dt seconds without a window, as fast as it is called
+    pub fn tick(&mut self, dt: f64) {
+        self.on_update(&UpdateArgs { dt });
+    }
+
+    /// the room and everything in it, in the same form sent to a joining game
+    pub fn observe(&self) -> Snapshot {
+        self.snapshot(0, 0)
+    }
+
+    /// what took every life lost so far this game, one player after another
+    pub fn lives_lost(&self) -> Vec<Cause> {
+        self.players.iter().flat_map(|player| player.lives_lost.iter().cloned()).collect()
+    }
+
+    /// sets the game up as the snapshot describes, with the room's walls for its level and mode,
+    /// so tests and tools can draw the same scene every time
+    /// game over and won snapshots are shown on those screens, every other one is shown playing
+    pub fn stage(&mut self, snapshot: &Snapshot) {
+        self.start_seeded(snapshot.mode, 0);
+        self.level = snapshot.level;
+        self.room = self.mode.room(&self.campaign, self.level);
+        self.start_level();
+        self.apply_snapshot(snapshot);
+        self.player_count = self.players.len();
+        self.state = match snapshot.screen {
+            Screen::GameOver => State::GameOver,
+            Screen::Won => State::Won,
+            _ => State::Playing,
+        };
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// true once the game has ended, won or lost
+    pub fn is_over(&self) -> bool {
+        matches!(self.state, State::GameOver | State::Won | State::EnterInitials { .. })
+    }
+}
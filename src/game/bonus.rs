@@ -0,0 +1,35 @@
+use super::{Game, Mode};
+
+impl Game {
+    /// adds the room clear bonus, only called when every robot in the room was destroyed
+    /// it goes to the shared score and to every player still in the room
+    pub(super) fn award_room_clear(&mut self) {
+        let bonus = self.settings.gameplay.room_clear_bonus;
+        if bonus > 0 {
+            self.score += bonus;
+            for player in self.players.iter_mut().filter(|player| player.is_alive()) {
+                player.score += bonus;
+            }
+            self.notify(format!("ROOM CLEAR BONUS {}", bonus));
+        }
+    }
+
+    /// gives a life for every extra life score that has been passed since the last check
+    /// the score is shared so in a two player game both players get the life
+    /// versus players have no lives to add to
+    pub(super) fn award_extra_lives(&mut self) {
+        if self.mode == Mode::Versus {
+            return
+        }
+        while let Some(&threshold) = self.settings.gameplay.extra_life_scores.get(self.next_extra_life) {
+            if self.score < threshold {
+                break
+            }
+            self.next_extra_life += 1;
+            for player in &mut self.players {
+                player.add_life();
+            }
+            self.notify("EXTRA LIFE".to_string());
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use config;
+use render::gif::GifWriter;
+use render::software::{self, Image, SpriteImages};
+
+use super::{Game, LOGICAL_SIZE};
+
+/// the folder in the config directory that screenshots and recordings are saved in
+pub const CAPTURE_DIR: &str = "screenshots";
+/// frames a second in recordings, a GIF frame lasts a whole number of hundredths of a second
+pub const RECORD_RATE: f64 = 20.0;
+pub const RECORD_DELAY: u16 = 5;
+
+/// a GIF being written on its own thread so encoding does not slow the game down
+/// since_frame: seconds of updates since the last frame was sent
+pub struct Recording {
+    path: PathBuf,
+    frames: Sender<Image>,
+    writer: JoinHandle<io::Result<()>>,
+    since_frame: f64,
+}
+
+/// screenshots and recordings are drawn again with the software renderer, so they need no OpenGL
+/// sprites: loaded the first time a picture is taken
+#[derive(Default)]
+pub struct Capture {
+    sprites: Option<SpriteImages>,
+    recording: Option<Recording>,
+}
+
+/// the UTC date and time as YYYY-MM-DD_HH-MM-SS, for file names that sort by when they were made
+fn timestamp(secs: u64) -> String {
+    // days to a civil date, counting from 1 March 0000 so the leap day ends the year
+    let days = (secs / 86400) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    let time = secs % 86400;
+    format!("{:04}-{:02}-{:02}_{:02}-{:02}-{:02}", year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
+
+/// berzerk-TIMESTAMP.EXTENSION in the capture folder, with a number added if one was already saved that second
+fn capture_path(extension: &str) -> PathBuf {
+    let dir = config::config_path(CAPTURE_DIR);
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+    let stem = format!("berzerk-{}", timestamp(secs));
+    let mut path = dir.join(format!("{}.{}", stem, extension));
+    let mut number = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.{}", stem, number, extension));
+        number += 1;
+    }
+    path
+}
+
+/// the name the capture was saved as, the folder is too long to show on screen
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or(String::new(), |name| name.to_string_lossy().to_uppercase())
+}
+
+impl Recording {
+    fn start(path: &Path, width: u32, height: u32) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = File::create(path)?;
+        let (frames, received) = mpsc::channel::<Image>();
+        let writer = thread::spawn(move || {
+            let mut out = BufWriter::new(file);
+            {
+                let mut gif = GifWriter::new(&mut out, width, height)?;
+                for frame in received {
+                    gif.frame(&frame, RECORD_DELAY)?;
+                }
+            }
+            out.flush()
+        });
+        Ok(Recording { path: path.to_path_buf(), frames, writer, since_frame: 0.0 })
+    }
+
+    /// waits for the frames sent so far to be written and closes the file
+    fn stop(self) -> io::Result<PathBuf> {
+        let Recording { path, frames, writer, .. } = self;
+        drop(frames);
+        match writer.join() {
+            Ok(result) => result.map(|_| path),
+            Err(_) => Err(io::Error::other("the recording thread panicked")),
+        }
+    }
+}
+
+impl Game {
+    pub fn is_recording(&self) -> bool {
+        self.capture.recording.is_some()
+    }
+
+    /// the current screen drawn at the game's own size, none if the sprites can not be loaded
+    fn capture_frame(&mut self) -> Option<Image> {
+        if self.capture.sprites.is_none() {
+            match SpriteImages::load() {
+                Ok(sprites) => self.capture.sprites = Some(sprites),
+                Err(_) => {
+                    self.notify("COULD NOT LOAD THE SPRITES".to_string());
+                    return None
+                },
+            }
+        }
+        let sprites = self.capture.sprites.take()?;
+        let image = software::render(self, &sprites, LOGICAL_SIZE[0] as u32, LOGICAL_SIZE[1] as u32);
+        self.capture.sprites = Some(sprites);
+        Some(image)
+    }
+
+    /// saves the current screen as a timestamped PNG
+    pub(super) fn screenshot(&mut self) {
+        let image = match self.capture_frame() {
+            Some(image) => image,
+            None => return,
+        };
+        let path = capture_path("png");
+        let saved = path.parent().map_or(Ok(()), fs::create_dir_all).map_err(|e| e.to_string())
+            .and_then(|_| image.save(&path));
+        match saved {
+            Ok(()) => self.notify(format!("SAVED {}", file_name(&path))),
+            Err(_) => self.notify("COULD NOT SAVE THE SCREENSHOT".to_string()),
+        }
+    }
+
+    /// starts recording to a timestamped GIF, or finishes the recording already going
+    pub(super) fn toggle_recording(&mut self) {
+        match self.capture.recording.take() {
+            Some(recording) => match recording.stop() {
+                Ok(path) => self.notify(format!("SAVED {}", file_name(&path))),
+                Err(_) => self.notify("COULD NOT SAVE THE RECORDING".to_string()),
+            },
+            None => {
+                let path = capture_path("gif");
+                match Recording::start(&path, LOGICAL_SIZE[0] as u32, LOGICAL_SIZE[1] as u32) {
+                    Ok(recording) => {
+                        self.capture.recording = Some(recording);
+                        self.record(1.0 / RECORD_RATE);
+                        if self.is_recording() {
+                            self.notify("RECORDING".to_string());
+                        }
+                    },
+                    Err(_) => self.notify("COULD NOT START RECORDING".to_string()),
+                }
+            },
+        }
+    }
+
+    /// sends a frame to the recording every so often as the game updates
+    pub(super) fn record(&mut self, dt: f64) {
+        let due = match self.capture.recording {
+            Some(ref mut recording) => {
+                recording.since_frame += dt;
+                recording.since_frame >= 1.0 / RECORD_RATE - 1e-9
+            },
+            None => false,
+        };
+        if !due {
+            return
+        }
+        let sent = match (self.capture_frame(), self.capture.recording.as_mut()) {
+            (Some(frame), Some(recording)) => {
+                recording.since_frame -= 1.0 / RECORD_RATE;
+                recording.frames.send(frame).is_ok()
+            },
+            _ => false,
+        };
+        if !sent {
+            // the frame could not be drawn or the writer stopped early, stopping the recording reports it
+            self.toggle_recording();
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_timestamp() {
+        assert!(timestamp(0) == "1970-01-01_00-00-00");
+        assert!(timestamp(1_700_000_000) == "2023-11-14_22-13-20");
+        assert!(timestamp(951_782_400) == "2000-02-29_00-00-00");
+    }
+
+    #[test]
+    fn test_recording_writes_a_gif() {
+        let path = ::std::env::temp_dir().join("berzerk-test-recording.gif");
+        let recording = Recording::start(&path, 4, 2).unwrap();
+        recording.frames.send(Image::new(4, 2)).unwrap();
+        recording.frames.send(Image::new(4, 2)).unwrap();
+        assert!(recording.stop().unwrap() == path);
+        let bytes = fs::read(&path).unwrap();
+        assert!(&bytes[..6] == b"GIF89a" && bytes[bytes.len() - 1] == 0x3b);
+        assert!(bytes.windows(2).filter(|pair| pair == &[0x21, 0xf9]).count() == 2);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_stopping_a_recording_notifies() {
+        use campaign::Campaign;
+        use highscores::HighScores;
+        use settings::Settings;
+
+        let path = ::std::env::temp_dir().join("berzerk-test-stop.gif");
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.capture.recording = Some(Recording::start(&path, 4, 2).unwrap());
+        g.toggle_recording();
+        assert!(!g.is_recording() && g.notices.len() == 1);
+        assert!(file_name(&path) == "BERZERK-TEST-STOP.GIF");
+        let _ = fs::remove_file(&path);
+    }
+}
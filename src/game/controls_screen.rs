@@ -0,0 +1,116 @@
+use piston::input::*;
+
+use controls::{self, Bindings, ACTIONS};
+use render::Renderer;
+
+use super::menu::Menu;
+use super::{Game, State, MAX_PLAYERS};
+
+const PLAYER: usize = 7;
+const RESET_DEFAULTS: usize = 8;
+const SAVE_AND_BACK: usize = 9;
+
+/// one line per action of the first player followed by the player, reset and save choices
+pub fn controls_menu(bindings: &Bindings) -> Menu {
+    let mut items: Vec<String> = ACTIONS.iter().map(|action| bindings.describe(*action)).collect();
+    items.push("EDITING PLAYER 1".to_string());
+    items.push("RESET DEFAULTS".to_string());
+    items.push("SAVE AND BACK".to_string());
+    let items: Vec<&str> = items.iter().map(|item| item.as_str()).collect();
+    Menu::new(&items)
+}
+
+impl Game {
+    /// opens the controls screen on the first player's keys
+    pub(super) fn open_controls(&mut self) {
+        self.controls_player = 0;
+        self.refresh_controls_menu();
+        self.controls_menu.reset();
+        self.state = State::Controls;
+    }
+
+    /// rewrites the action lines after a binding changes or the other player is picked
+    fn refresh_controls_menu(&mut self) {
+        for (i, action) in ACTIONS.iter().enumerate() {
+            let line = self.settings.bindings(self.controls_player).describe(*action);
+            self.controls_menu.set_item(i, line.as_str());
+        }
+        let player = format!("EDITING PLAYER {}", self.controls_player + 1);
+        self.controls_menu.set_item(PLAYER, player.as_str());
+    }
+
+    /// choosing an action waits for a key to add to it, backspace clears the highlighted action
+    pub(super) fn controls_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let Button::Keyboard(key) = *button {
+            if key == Key::Backspace {
+                if let Some(action) = ACTIONS.get(self.controls_menu.highlighted()) {
+                    self.settings.bindings_mut(self.controls_player).clear(*action);
+                    self.refresh_controls_menu();
+                }
+                return
+            }
+            match self.controls_menu.navigate(key) {
+                Some(PLAYER) => {
+                    self.controls_player = (self.controls_player + 1) % MAX_PLAYERS;
+                    self.refresh_controls_menu();
+                },
+                Some(RESET_DEFAULTS) => {
+                    *self.settings.bindings_mut(self.controls_player) = if self.controls_player == 0 {
+                        Bindings::default()
+                    } else {
+                        Bindings::second_player()
+                    };
+                    self.refresh_controls_menu();
+                },
+                Some(SAVE_AND_BACK) => {
+                    self.save_settings_from_menu();
+                    self.state = State::Options;
+                },
+                Some(i) => {
+                    if let Some(action) = ACTIONS.get(i) {
+                        self.state = State::Rebind(*action);
+                    }
+                },
+                None => (),
+            }
+        }
+    }
+
+    /// the next bindable key pressed is added to the action being rebound
+    /// and taken away from the other player so one key never moves both
+    pub(super) fn rebind_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let (State::Rebind(action), Button::Keyboard(key)) = (self.state, *button) {
+            if controls::BINDABLE_KEYS.contains(&key) {
+                for player in 0..MAX_PLAYERS {
+                    self.settings.bindings_mut(player).unbind(key);
+                }
+                self.settings.bindings_mut(self.controls_player).bind(action, key);
+                self.refresh_controls_menu();
+                self.state = State::Controls;
+            }
+        }
+    }
+
+    /// the game stays frozen behind the controls screen
+    pub(super) fn controls_update(&mut self, _args: &UpdateArgs) {
+    }
+
+    pub(super) fn controls_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_banner("CONTROLS", -250.0, renderer);
+        self.controls_menu.draw(self.dimensions[0]/2.0-200.0, self.dimensions[1]/2.0-200.0, renderer);
+        self.draw_banner("BACKSPACE CLEARS KEYS", 200.0, renderer);
+    }
+
+    pub(super) fn rebind_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        if let State::Rebind(action) = self.state {
+            let prompt = format!("PRESS A KEY FOR {}", action.label());
+            self.draw_banner(prompt.as_str(), 0.0, renderer);
+        }
+    }
+}
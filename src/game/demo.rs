@@ -0,0 +1,63 @@
+use bot::Bot;
+use environment::Observation;
+use render::Renderer;
+
+use super::{Driver, Game, Mode, State};
+
+/// seconds on the title screen without a key press before the demo starts
+const ATTRACT_DELAY: f64 = 20.0;
+
+/// the attract demo, the bot playing a one player game from the title screen until a key is pressed
+/// player_count: the number of players picked on the title screen, put back when the demo ends
+pub struct Demo {
+    bot: Bot,
+    player_count: usize,
+}
+
+impl Game {
+    /// counts the time the title screen has been left alone and starts the demo when it is long enough
+    /// a network game never shows the demo
+    pub(super) fn title_idle(&mut self, dt: f64) {
+        self.idle_time += dt;
+        if self.idle_time >= ATTRACT_DELAY && self.network.is_none() {
+            self.start_demo();
+        }
+    }
+
+    fn start_demo(&mut self) {
+        self.demo = Some(Demo {
+            bot: Bot::new(),
+            player_count: self.player_count,
+        });
+        self.set_player_count(1);
+        self.start_game(Mode::Campaign);
+    }
+
+    /// back to the title screen with the keyboard in charge again
+    pub(super) fn stop_demo(&mut self) {
+        if let Some(demo) = self.demo.take() {
+            self.set_player_count(demo.player_count);
+            self.driver = Driver::Keys;
+            self.idle_time = 0.0;
+            self.notices.clear();
+            self.state = State::Title;
+        }
+    }
+
+    /// the bot picks the first player's move before each update of the demo
+    pub(super) fn demo_update(&mut self) {
+        if let Some(mut demo) = self.demo.take() {
+            if self.state == State::Playing {
+                let action = demo.bot.act(&Observation::of(self));
+                self.drive(action.dir, action.fire);
+            }
+            self.demo = Some(demo);
+        }
+    }
+
+    pub(super) fn demo_draw<R: Renderer>(&self, renderer: &mut R) {
+        if self.demo.is_some() {
+            self.draw_banner("DEMO PRESS ANY KEY", -200.0, renderer);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use piston::input::*;
+
+use controls::Action;
+use render::Renderer;
+
+use super::{Game, Mode};
+
+impl Game {
+    pub(super) fn game_over_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let Button::Keyboard(key) = *button {
+            if self.action_for(key).map(|(_, action)| action) == Some(Action::Restart) {
+                self.hard_reset();
+            }
+        }
+    }
+
+    pub(super) fn game_over_update(&mut self, args: &UpdateArgs) {
+        self.tick_cooldown(args);
+    }
+
+    pub(super) fn game_over_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_room(renderer);
+        self.draw_banner("GAME OVER PRESS R TO RESTART", 0.0, renderer);
+        if self.mode == Mode::Endless {
+            let cleared = format!("{} ROOMS CLEARED", self.rooms_cleared);
+            self.draw_banner(&cleared, 45.0, renderer);
+        }
+        self.draw_high_scores(60.0, 80.0, renderer);
+    }
+}
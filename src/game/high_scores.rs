@@ -0,0 +1,87 @@
+use piston::input::*;
+
+use config;
+use controls;
+use highscores::{HIGH_SCORES_FILE, INITIALS_LEN};
+use render::Renderer;
+
+use super::{Game, State, BLACK};
+
+const TABLE_LINE_HEIGHT: f64 = 30.0;
+
+impl Game {
+    /// ends the game, asking for initials first if the score made the high score table
+    pub(super) fn end_game(&mut self, won: bool) {
+        if self.high_scores.qualifies(self.score) {
+            self.initials = Default::default();
+            self.state = State::EnterInitials { won };
+        } else {
+            self.state = if won { State::Won } else { State::GameOver };
+        }
+    }
+
+    /// up and down change the letter, left and right pick which letter,
+    /// typing a letter sets it and enter saves the score
+    pub(super) fn enter_initials_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let (State::EnterInitials { won }, Button::Keyboard(key)) = (self.state, *button) {
+            match key {
+                Key::Up => self.initials.cycle(true),
+                Key::Down => self.initials.cycle(false),
+                Key::Left | Key::Backspace => self.initials.move_cursor(false),
+                Key::Right => self.initials.move_cursor(true),
+                Key::Return => {
+                    self.high_scores.insert(&self.initials.text(), self.score, self.level);
+                    if self.high_scores.save(&config::config_path(HIGH_SCORES_FILE)).is_err() {
+                        self.notify("COULD NOT SAVE HIGH SCORES".to_string());
+                    }
+                    self.state = if won { State::Won } else { State::GameOver };
+                },
+                _ => {
+                    let name = controls::key_name(key);
+                    if name.len() == 1 {
+                        if let Some(letter) = name.chars().next() {
+                            self.initials.type_letter(letter);
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    pub(super) fn enter_initials_update(&mut self, args: &UpdateArgs) {
+        self.tick_cooldown(args);
+    }
+
+    pub(super) fn enter_initials_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_banner("NEW HIGH SCORE", -100.0, renderer);
+        let score = format!("{}", self.score);
+        self.draw_banner(score.as_str(), -50.0, renderer);
+
+        let letters = self.initials.text();
+        for (i, letter) in letters.chars().enumerate() {
+            let x = self.dimensions[0]/2.0 - 60.0 + 50.0 * i as f64;
+            let y = self.dimensions[1]/2.0 + 30.0;
+            renderer.text(self.settings.video.text_color, 48, letter.to_string().as_str(), x, y);
+            if i == self.initials.cursor() {
+                renderer.rectangle(self.settings.video.text_color, [x, y + 10.0, 25.0, 4.0]);
+            }
+        }
+        let hint = format!("ENTER {} LETTERS AND PRESS ENTER", INITIALS_LEN);
+        self.draw_banner(hint.as_str(), 120.0, renderer);
+    }
+
+    /// draws the high score table with its top left corner at x, y over a black box
+    pub(super) fn draw_high_scores<R: Renderer>(&self, x: f64, y: f64, renderer: &mut R) {
+        let entries = self.high_scores.entries();
+        let height = TABLE_LINE_HEIGHT * (entries.len() as f64 + 1.0) + 20.0;
+        renderer.rectangle(BLACK, [x - 15.0, y - 35.0, 230.0, height]);
+        renderer.text(self.settings.video.text_color, 30, "HIGH SCORES", x, y);
+        for (i, entry) in entries.iter().enumerate() {
+            let line = format!("{:2}. {}  {}", i + 1, entry.initials, entry.score);
+            renderer.text(self.settings.video.text_color, 26, line.as_str(), x, y + TABLE_LINE_HEIGHT * (i as f64 + 1.0));
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use piston::input::Key;
+use render::Renderer;
+
+use super::{YELLOW, BLUE};
+
+const MENU_LINE_HEIGHT: f64 = 40.0;
+
+/// a vertical list of choices moved through with the arrow keys
+/// items: the text shown for each choice
+/// selected: index of the highlighted choice
+pub struct Menu {
+    items: Vec<String>,
+    selected: usize,
+}
+
+impl Menu {
+    /// creates a menu with the first item selected
+    pub fn new(items: &[&str]) -> Self {
+        Menu {
+            items: items.iter().map(|item| item.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    /// index of the highlighted item
+    pub fn highlighted(&self) -> usize {
+        self.selected
+    }
+
+    /// moves the highlight back to the first item
+    pub fn reset(&mut self) {
+        self.selected = 0;
+    }
+
+    /// changes the text of an item, used for items that show a value
+    pub fn set_item(&mut self, index: usize, item: &str) {
+        if let Some(existing) = self.items.get_mut(index) {
+            *existing = item.to_string();
+        }
+    }
+
+    /// moves the highlight with up and down, wrapping at the ends
+    /// returns the index of the highlighted item when it is chosen with space or enter
+    pub fn navigate(&mut self, key: Key) -> Option<usize> {
+        match key {
+            Key::Up => {
+                self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+                None
+            },
+            Key::Down => {
+                self.selected = (self.selected + 1) % self.items.len();
+                None
+            },
+            Key::Return | Key::Space => Some(self.selected),
+            _ => None,
+        }
+    }
+
+    /// draws the items starting at x, y with the selected one highlighted
+    pub fn draw<R: Renderer>(&self, x: f64, y: f64, renderer: &mut R) {
+        for (i, item) in self.items.iter().enumerate() {
+            let (color, line) = if i == self.selected {
+                (YELLOW, format!("> {}", item))
+            } else {
+                (BLUE, format!("  {}", item))
+            };
+            renderer.text(color, 32, line.as_str(), x, y + MENU_LINE_HEIGHT * i as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_navigate_wraps() {
+        let mut menu = Menu::new(&["A", "B", "C"]);
+        menu.navigate(Key::Up);
+        assert!(menu.navigate(Key::Return) == Some(2));
+        menu.navigate(Key::Down);
+        assert!(menu.navigate(Key::Return) == Some(0));
+    }
+
+    #[test]
+    fn test_navigate_choose() {
+        let mut menu = Menu::new(&["A", "B"]);
+        assert!(menu.navigate(Key::Down).is_none());
+        assert!(menu.navigate(Key::Return) == Some(1));
+        menu.reset();
+        assert!(menu.navigate(Key::Space) == Some(0));
+    }
+}
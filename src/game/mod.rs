@@ -0,0 +1,999 @@
+extern crate piston;
+extern crate graphics;
+extern crate glutin_window;
+extern crate opengl_graphics;
+extern crate rand;
+extern crate find_folder;
+
+use piston::event_loop::*;
+use piston::input::*;
+use opengl_graphics::GlyphCache;
+use opengl_graphics::GlGraphics;
+use glutin_window::GlutinWindow as Window;
+use piston::window::Window as PistonWindow;
+use self::rand::{Rng, XorShiftRng};
+
+use models::player::{Cause, Player};
+use models::vector::Vector;
+use models::bullet::Bullet;
+use models::enemy::{Enemy, EnemyKind};
+use render::{GlRenderer, Renderer};
+use sprites::Sprites;
+
+use campaign::{Campaign, Exit, Progress, Room, WinCondition};
+use controls::{Action, HeldKeys};
+use difficulty::LevelParams;
+use config;
+use settings::{Settings, MIN_WINDOW_SIZE, SETTINGS_FILE};
+use highscores::{HighScores, Initials};
+use music;
+use std::io;
+
+mod state;
+mod menu;
+mod title;
+mod playing;
+mod paused;
+mod options;
+mod controls_screen;
+mod notice;
+mod view;
+mod high_scores;
+mod bonus;
+mod transition;
+mod game_over;
+mod won;
+mod mode;
+mod network;
+mod versus;
+mod agent;
+mod demo;
+mod capture;
+
+pub use self::state::State;
+use self::menu::Menu;
+use self::paused::PAUSE_ITEMS;
+use self::title::TITLE_ITEMS;
+use self::options::options_menu;
+use self::controls_screen::controls_menu;
+use self::notice::Notice;
+use self::view::View;
+use self::transition::ROOM_TRANSITION_TIME;
+pub use self::mode::Mode;
+use self::network::Network;
+use self::versus::Outcome;
+use self::demo::Demo;
+use self::capture::Capture;
+pub use self::agent::{seeded_rng, Driver};
+pub use self::capture::{RECORD_DELAY, RECORD_RATE};
+
+const FIRE_COOLDOWN: f64 = 1.5;
+
+/// the size of the play area the game is simulated in
+/// the window shows it scaled to fit with black bars filling the rest
+pub const LOGICAL_SIZE: [f64; 2] = [900.0, 600.0];
+
+/// why run returned
+/// Closed: the window was closed or the player quit
+/// RebuildWindow: the video settings changed and the window has to be made again
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RunEnd {
+    Closed,
+    RebuildWindow,
+}
+
+/// houses the direction that a game object may point in
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Direction {
+    WEST,
+    NORTH,
+    EAST,
+    SOUTH
+}
+
+/// Contains states and objects used in berzerk
+pub struct Game {
+	players: Vec<Player>,
+    player_count: usize,
+    enemy_bullets: Vec<Bullet>,    
+    enemies: Vec<Enemy>,    
+	dimensions: [f64;2],
+	score: u32,
+    level:u32,
+    mode: Mode,
+    campaign: Campaign,
+    room: Room,
+    rooms_cleared: u32,
+    play_time: f64,
+    level_params: LevelParams,
+    fire_cooldown: f64,    
+    pub walls: Vec<[f64;4]>, //make [f64;4] a Wall object with [x0 y0 x1 y1]
+    state: State,
+    title_menu: Menu,
+    pause_menu: Menu,
+    options_menu: Menu,
+    options_return: State,
+    controls_menu: Menu,
+    settings: Settings,
+    controls_player: usize,
+    held_keys: Vec<HeldKeys>,
+    notices: Vec<Notice>,
+    rebuild_window: bool,
+    high_scores: HighScores,
+    initials: Initials,
+    next_extra_life: usize,
+    versus_outcome: Option<Outcome>,
+    network: Option<Network>,
+    driver: Driver,
+    rng: XorShiftRng,
+    silent: bool,
+    demo: Option<Demo>,
+    idle_time: f64,
+    capture: Capture,
+}
+
+pub const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+pub const YELLOW: [f32; 4] = [1.0, 1.0, 0.5, 1.0];
+pub const BLUE: [f32; 4] = [0.5, 0.6, 0.7, 1.0];
+pub const RED: [f32; 4] = [1.0, 0.2, 0.2, 1.0];
+pub const FPS: u64 = 60;
+/// the most players that can play at once on one keyboard
+pub const MAX_PLAYERS: usize = 2;
+
+impl Game {
+	pub fn new(settings: Settings, high_scores: HighScores, campaign: Campaign) -> Self {
+		Game {
+			players: vec![Player::new(75.0, LOGICAL_SIZE[1] / 2.0)],
+            player_count: 1,
+            enemy_bullets:Vec::<Bullet>::new(),            
+			dimensions: LOGICAL_SIZE,
+            enemies: Vec::new(),            
+            score: 0,
+            level:1,
+            mode: Mode::Campaign,
+            room: campaign.room(1).clone(),
+            campaign,
+            rooms_cleared: 0,
+            play_time: 0.0,
+            level_params: settings.gameplay.difficulty.level(1),
+            fire_cooldown: 0.0,            
+            walls: Vec::new(),  
+            state: State::Title,
+            title_menu: Menu::new(&TITLE_ITEMS),
+            pause_menu: Menu::new(&PAUSE_ITEMS),
+            options_menu: options_menu(&settings),
+            options_return: State::Title,
+            controls_menu: controls_menu(&settings.controls),
+            settings,
+            controls_player: 0,
+            held_keys: (0..MAX_PLAYERS).map(|_| HeldKeys::default()).collect(),
+            notices: Vec::new(),
+            rebuild_window: false,
+            high_scores,
+            initials: Initials::default(),
+            next_extra_life: 0,
+            versus_outcome: None,
+            network: None,
+            driver: Driver::Keys,
+            rng: rand::thread_rng().gen(),
+            silent: false,
+            demo: None,
+            idle_time: 0.0,
+            capture: Capture::default(),
+		}
+	}
+
+    /// the settings the window should be built with
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// keeps this game quiet whatever the sound setting is, for games with no one listening
+    pub fn set_silent(&mut self, silent: bool) {
+        self.silent = silent;
+    }
+
+    /// the volume sounds play at, 0 when the sound is off or the game is silent
+    pub fn volume(&self) -> f32 {
+        if !self.silent && self.settings.audio.sound_on { self.settings.audio.volume } else { 0.0 }
+    }
+
+    fn play_sound(&self, sound: u32) {
+        music::play(sound, self.volume());
+    }
+
+
+//pos[x0, y0, x1, y1] for opposite points of rect
+    pub fn make_border<R: Renderer>(&self, renderer: &mut R, pos: [f64;4]) {
+        renderer.rectangle(self.settings.video.wall_color, [pos[0], pos[1], pos[2] - pos[0], pos[3] - pos[1]]);
+    }
+
+    /// lays out the walls of the room in logical coordinates
+    pub fn make_level_borders(&mut self) {
+        let half_width =self.dimensions[0]/2.0;
+        let half_height =self.dimensions[1]/2.0;            
+        let quarter_width = self.dimensions[0]/4.0;     
+        let quarter_height = self.dimensions[1]/4.0;  
+
+        let left_vertical = [5.0,5.0,30.0,self.dimensions[1]-75.0];
+        let left_top =[30.0,5.0,half_width-125.0,30.0];
+        let right_top = [half_width+125.0,5.0,self.dimensions[0]-5.0,30.0];
+        let right_vertical = [self.dimensions[0]-30.0,25.0,self.dimensions[0]-5.0,self.dimensions[1]-75.0];
+        let left_bottom = [25.0,self.dimensions[1]-100.0,(half_width)-125.0,self.dimensions[1]-75.0];
+        let right_bottom = [half_width+125.0,self.dimensions[1]-100.0,self.dimensions[0]-5.0,self.dimensions[1]-75.0];
+        let middle_top_vert = [quarter_width,quarter_height,quarter_width+25.0,quarter_height*3.0-75.0];
+        let middle_right_vert = [quarter_width*3.0,quarter_height,quarter_width*3.0+25.0,quarter_height*3.0-75.0];
+        let middle_middle = [quarter_width+25.0,half_height-50.0,quarter_width*3.0,half_height-25.0];
+
+        // in update we use this to check for collision with enemy and eventually player
+        self.add_walls(left_vertical,left_top,right_top,right_vertical,left_bottom,right_bottom,
+            middle_top_vert, middle_right_vert, middle_middle);
+    }
+
+    /// the wall that closes the exit at the top in rooms that end when every robot is destroyed
+    fn exit_door(&self) -> [f64;4] {
+        [self.dimensions[0]/2.0-125.0, 5.0, self.dimensions[0]/2.0+125.0, 30.0]
+    }
+
+    /// draws the border pieces and the middle part of the room
+    fn draw_walls<R: Renderer>(&self, renderer: &mut R) {
+        for wall in &self.walls {
+            self.make_border(renderer, *wall);
+        }
+    }
+
+//middle_top_vert, middle_right_vert, middle_middle
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_walls(&mut self, lf:[f64;4],lt:[f64;4],rt:[f64;4],rv:[f64;4],lb:[f64;4],rb:[f64;4],mtv:[f64;4],mrv:[f64;4],mm:[f64;4],) {
+        self.walls.push(lf);
+        self.walls.push(lt);        
+        self.walls.push(rt);
+        self.walls.push(rv);
+        self.walls.push(lb);
+        self.walls.push(rb);
+        self.walls.push(mtv);
+        self.walls.push(mrv);
+        self.walls.push(mm);
+    }
+
+    /// draws the current screen with any renderer, window is the size drawn to in pixels
+    /// the play area is scaled to fit with black bars filling the rest
+    pub fn draw<R: Renderer>(&mut self, renderer: &mut R, window: [f64; 2]) {
+        let view = View::fit(window, self.dimensions);
+        renderer.set_view([0.0, 0.0], 1.0);
+        renderer.clear(BLACK);
+        // everything is drawn in logical coordinates and scaled to the window
+        renderer.set_view(view.offset, view.scale);
+        match self.state {
+            State::Title => self.title_draw(renderer),
+            State::Playing => self.playing_draw(renderer),
+            State::Paused => self.paused_draw(renderer),
+            State::Options => self.options_draw(renderer),
+            State::Controls => self.controls_draw(renderer),
+            State::Rebind(_) => self.rebind_draw(renderer),
+            State::RoomTransition(_) => self.transition_draw(renderer),
+            State::EnterInitials { .. } => self.enter_initials_draw(renderer),
+            State::GameOver => self.game_over_draw(renderer),
+            State::Won => self.won_draw(renderer),
+            State::Joined => self.joined_draw(renderer),
+            State::Quit => (),
+        }
+        self.draw_notices(renderer);
+        renderer.set_view([0.0, 0.0], 1.0);
+        for bar in view.bars() {
+            renderer.rectangle(BLACK, [bar[0], bar[1], bar[2] - bar[0], bar[3] - bar[1]]);
+        }
+    }
+
+	fn on_draw(&mut self, args: &RenderArgs, gl: &mut GlGraphics, glyph_cache: &mut GlyphCache, sprites: &Sprites) {
+        gl.draw(args.viewport(), |c, gl| {
+            let mut renderer = GlRenderer::new(c, gl, glyph_cache, sprites);
+            self.draw(&mut renderer, args.window_size);
+            // only in the window, so it is not in the recording
+            if self.is_recording() {
+                renderer.text(RED, 24, "REC", args.window_size[0] - 60.0, 30.0);
+            }
+        });
+    }
+
+    /// draws the room, everything in it and the score, level and lives
+    /// shared by every state that shows the maze behind it
+    fn draw_room<R: Renderer>(&self, renderer: &mut R) {
+        for player in &self.players {
+            for bullet in &player.bullets {
+                bullet.draw(renderer);
+            }
+        }
+
+        for bullet in &self.enemy_bullets {
+            bullet.draw(renderer);
+        }
+
+        for enemy in &self.enemies {
+            enemy.draw(renderer);
+        }
+
+        self.draw_walls(renderer);
+        for player in self.players.iter().filter(|player| player.is_alive()) {
+            player.draw(renderer);
+        }
+
+        // with two players each gets a row of hearts and their own score next to it
+        // and the shared score moves left to make room, in versus the kills are shown instead
+        let two_players = self.players.len() > 1;
+        let versus = self.mode == Mode::Versus;
+        for (i, player) in self.players.iter().enumerate() {
+            let row_y = if two_players { self.dimensions[1]-50.0 + 30.0 * i as f64 } else { self.dimensions[1]-35.0 };
+            let mut pos_heart = (self.dimensions[1]/4.0)*3.5;
+            for _ in 0..player.health {
+                pos_heart +=35.0;
+                player.draw_lives(pos_heart, row_y, renderer);
+            }                
+            if two_players {
+                let tally = if versus { player.kills } else { player.score };
+                renderer.text(player.color, 26, format!("{}", tally).as_str(), (self.dimensions[1]/4.0)*3.5-25.0, row_y+10.0);
+            }
+        }
+
+        if !versus {
+            let score_x = if two_players { self.dimensions[0]/2.0-60.0 } else { self.dimensions[0]/2.0 };
+            renderer.text(self.settings.video.text_color, 38, format!("{}", self.score).as_str(), score_x,self.dimensions[1]-25.0);
+
+            renderer.text(self.settings.video.text_color, 38, format!("{}", self.level).as_str(), 50.0,self.dimensions[1]-25.0);
+        }
+
+        if self.mode == Mode::Endless {
+            renderer.text(self.settings.video.text_color, 38, format!("ROOMS {}", self.rooms_cleared).as_str(), 120.0,self.dimensions[1]-25.0);
+        } else if let Some(time_left) = self.time_left() {
+            renderer.text(self.settings.video.text_color, 38, format!("TIME {}", time_left.ceil()).as_str(), 120.0,self.dimensions[1]-25.0);
+        }
+    }
+
+    /// writes a line of text in the middle of the window, offset vertically by dy
+    fn draw_banner<R: Renderer>(&self, message: &str, dy: f64, renderer: &mut R) {
+        renderer.text(self.settings.video.text_color, 38, message, self.dimensions[0]/2.0-95.0,self.dimensions[1]/2.0+dy);
+    }
+
+    /// moves every player's bullets, a robot that is hit scores for the player who shot it
+    fn player_bullet_check(&mut self) {
+        let volume = self.volume();
+        for player in &mut self.players {
+            for bullet in &mut player.bullets {
+                bullet.update();
+                for enemy in &mut self.enemies {
+                    if bullet.collides_enemy(enemy) {
+                        bullet.alive = false;
+                        enemy.alive = false;
+                        if self.fire_cooldown <= 0.0 {                    
+                            music::play(2, volume);
+                            self.fire_cooldown = FIRE_COOLDOWN;
+                        }
+                        player.score += 50;
+                        self.score += 50;
+                    }
+                }  
+                for wall in &self.walls {
+                    if bullet.collides_wall(wall){
+                        bullet.alive = false;
+                    }                 
+                }             
+            }
+        }         
+        if self.settings.gameplay.friendly_fire || self.mode == Mode::Versus {
+            self.friendly_fire_check();
+        }
+    }
+
+    /// a player's bullet that hits the other player takes one of their lives
+    /// in versus that is a kill for the player who shot
+    fn friendly_fire_check(&mut self) {
+        let mut hits = Vec::new();
+        for (shooter, player) in self.players.iter().enumerate() {
+            for (b, bullet) in player.bullets.iter().enumerate() {
+                for (target, other) in self.players.iter().enumerate() {
+                    if shooter != target && bullet.alive && other.is_alive() && bullet.collides_p(other) {
+                        hits.push((shooter, b, target));
+                    }
+                }
+            }
+        }
+        for (shooter, b, target) in hits {
+            self.players[shooter].bullets[b].alive = false;
+            self.players[target].lose_life(Cause::PlayerBullet);
+            if self.mode == Mode::Versus && !self.players[target].is_alive() {
+                self.players[shooter].kills += 1;
+            }
+            if self.fire_cooldown <= 0.0 {                    
+                self.play_sound(1);
+                self.fire_cooldown = FIRE_COOLDOWN;                    
+            }
+        }
+    }
+
+    fn enemy_bullet_check(&mut self) {
+        let volume = self.volume();
+        for bullet in &mut self.enemy_bullets {
+            bullet.update();         
+            for player in self.players.iter_mut().filter(|player| player.is_alive()) {
+                if bullet.alive && bullet.collides_p(player) {
+                    bullet.alive = false;
+                    player.lose_life(Cause::RobotBullet);
+                    if self.fire_cooldown <= 0.0 {                    
+                        music::play(1, volume);
+                        self.fire_cooldown = FIRE_COOLDOWN;                    
+                    }
+                }
+            }
+            for wall in &self.walls {
+                if bullet.collides_wall(wall){
+                    bullet.alive = false;
+                }                 
+            }                      
+        }         
+    }
+
+    /// picks one of the robots that can shoot to fire, using the level's fire chance
+    fn enemy_chance_shoot(&mut self) {
+        let shooters: Vec<usize> = (0..self.enemies.len())
+            .filter(|&i| self.enemies[i].kind.can_shoot())
+            .collect();
+        if !shooters.is_empty() {
+            let chance_shot: f64 = self.rng.gen();
+            if chance_shot < self.level_params.fire_chance {
+                let index_enemy_shooting = shooters[self.rng.gen_range(0, shooters.len())];
+                let enemy_shooting = &self.enemies[index_enemy_shooting];
+                if self.fire_cooldown <= 0.0 {                    
+                    self.play_sound(3);
+                    self.fire_cooldown = FIRE_COOLDOWN;
+                    self.enemy_bullets.push(
+                        Bullet::with_speed(enemy_shooting.pos.x, enemy_shooting.pos.y, enemy_shooting.dir,
+                            self.level_params.bullet_speed)
+                    );                     
+                }   
+            }
+        }        
+    }
+
+    /// robots chase the nearest player still in the room
+    fn enemy_update(&mut self) {
+        let positions: Vec<Vector> = self.players.iter()
+            .filter(|player| player.is_alive())
+            .map(|player| Vector::new(player.pos.x, player.pos.y))
+            .collect();
+        let targets: Vec<&Vector> = positions.iter().collect();
+        let volume = self.volume();
+        for enemy in &mut self.enemies {
+            enemy.update(&targets, &mut self.rng);            
+            for wall in &self.walls {
+                if enemy.collides(wall) {
+                    enemy.alive = false;
+                    if self.fire_cooldown <= 0.0 {                    
+                        music::play(2, volume);
+                        self.fire_cooldown = FIRE_COOLDOWN;                        
+                    }
+                    self.score += 50;
+                    return
+                }
+                for player in self.players.iter_mut().filter(|player| player.is_alive()) {
+                    if player.collides_enemy(enemy) {
+                        enemy.alive = false;
+                        player.lose_life(Cause::Robot);
+                        player.place_random(self.dimensions, &mut self.rng); 
+                        if self.fire_cooldown <= 0.0 {
+                            music::play(1, volume);   
+                            self.fire_cooldown = FIRE_COOLDOWN;                                                   
+                        }                    
+                        return                 
+                    }
+                }
+            }
+        }        
+    }
+
+    fn wall_update(&mut self) {
+        let volume = self.volume();
+        for wall in &self.walls {
+            for player in self.players.iter_mut().filter(|player| player.is_alive()) {
+                if player.collides(wall){
+                    player.lose_life(Cause::Wall);
+                    player.place_random(self.dimensions, &mut self.rng);  
+                    if self.fire_cooldown <= 0.0 {
+                       music::play(1, volume); 
+                       self.fire_cooldown = FIRE_COOLDOWN;
+                    }
+                    match player.dir {
+                        Direction::NORTH => player.pos.y += 50.0,
+                        Direction::SOUTH => player.pos.y -= 50.0,
+                        Direction::EAST => player.pos.x -= 50.0,
+                        Direction::WEST => player.pos.x += 50.0                }
+                }          
+            }
+        }        
+    }
+
+    fn on_update(&mut self, args: &UpdateArgs) {
+        self.host_receive();
+        self.tick_notices(args.dt);
+        self.demo_update();
+        match self.state {
+            State::Title => self.title_update(args),
+            State::Playing => self.playing_update(args),
+            State::Paused => self.paused_update(args),
+            State::Options => self.options_update(args),
+            State::Controls | State::Rebind(_) => self.controls_update(args),
+            State::RoomTransition(_) => self.transition_update(args),
+            State::EnterInitials { .. } => self.enter_initials_update(args),
+            State::GameOver => self.game_over_update(args),
+            State::Won => self.won_update(args),
+            State::Joined => self.joined_update(args),
+            State::Quit => (),
+        }
+        // the demo goes back to the title instead of showing how it ended
+        if self.demo.is_some() && self.is_over() {
+            self.stop_demo();
+        }
+        self.host_send();
+    }
+
+    fn input(&mut self, button: &Button, is_press: bool) {
+        self.track_held_keys(button, is_press);
+        // taking pictures is not playing, so it leaves the demo running
+        if is_press && *button == Button::Keyboard(Key::F12) {
+            self.screenshot();
+            return
+        }
+        if is_press && *button == Button::Keyboard(Key::F9) {
+            self.toggle_recording();
+            return
+        }
+        if is_press {
+            self.idle_time = 0.0;
+            // any key ends the demo and is not used for anything else
+            if self.demo.is_some() {
+                self.stop_demo();
+                return
+            }
+        }
+        if is_press && *button == Button::Keyboard(Key::F11) {
+            self.toggle_fullscreen();
+            return
+        }
+        match self.state {
+            State::Title => self.title_input(button, is_press),
+            State::Playing => self.playing_input(button, is_press),
+            State::Paused => self.paused_input(button, is_press),
+            State::Options => self.options_input(button, is_press),
+            State::Controls => self.controls_input(button, is_press),
+            State::Rebind(_) => self.rebind_input(button, is_press),
+            State::RoomTransition(_) => self.transition_input(button, is_press),
+            State::EnterInitials { .. } => self.enter_initials_input(button, is_press),
+            State::GameOver => self.game_over_input(button, is_press),
+            State::Won => self.won_input(button, is_press),
+            State::Joined => self.joined_input(button, is_press),
+            State::Quit => (),
+        }
+    }    
+
+    /// keeps track of the movement keys held down in every state
+    /// so the player moves right away if a key is still held when play resumes
+    fn track_held_keys(&mut self, button: &Button, is_press: bool) {
+        if let Button::Keyboard(key) = *button {
+            for (player, held_keys) in self.held_keys.iter_mut().enumerate() {
+                if !is_press {
+                    held_keys.release(key);
+                } else if let Some(dir) = self.settings.bindings(player).action_for(key).and_then(|action| action.direction()) {
+                    held_keys.press(key, dir);
+                }
+            }
+        }
+    }
+
+    /// the player and action a key is bound to, the first player's keys win if both use it
+    /// in a network game only the first player's keys are used
+    fn action_for(&self, key: Key) -> Option<(usize, Action)> {
+        (0..self.local_players())
+            .filter_map(|player| self.settings.bindings(player).action_for(key).map(|action| (player, action)))
+            .next()
+    }
+
+    /// switches between fullscreen and a window, which needs the window to be rebuilt
+    fn toggle_fullscreen(&mut self) {
+        self.settings.video.fullscreen = !self.settings.video.fullscreen;
+        self.rebuild_window = true;
+    }
+
+    /// writes the settings to the user's config directory
+    pub fn save_settings(&self) -> io::Result<()> {
+        self.settings.save(&config::config_path(SETTINGS_FILE))
+    }
+
+    /// saves the settings from a menu, saying so on screen if it fails
+    fn save_settings_from_menu(&mut self) {
+        if self.save_settings().is_err() {
+            self.notify("COULD NOT SAVE SETTINGS".to_string());
+        }
+    }
+
+    /// counts down the cooldown that keeps sounds from overlapping
+    fn tick_cooldown(&mut self, args: &UpdateArgs) {
+        if self.fire_cooldown > 0.0 {
+            self.fire_cooldown -= args.dt;
+        }
+    }
+
+    /// runs the game in a window until it is closed or the window has to be rebuilt
+	pub fn run(&mut self, window: &mut Window,
+               gl: &mut GlGraphics,
+               glyph_cache: &mut GlyphCache,
+               sprites: &Sprites) -> RunEnd {
+
+		let mut events = Events::new(EventSettings::new());
+        events.set_ups(FPS);
+        self.rebuild_window = false;
+      
+        while let Some(e) = events.next(window) {
+            if let Some(r) = e.update_args() {
+                self.on_update(&r);
+                self.record(r.dt);
+            }               
+
+            if let Some(k) = e.press_args() {
+                self.input(&k,true);
+            } else if let Some(k) = e.release_args() {
+                self.input(&k, false);
+            }                 
+
+            // clicking away from the window pauses the game
+            // key releases are not seen while unfocused so held keys are forgotten
+            if let Some(false) = e.focus_args() {
+                for held_keys in &mut self.held_keys {
+                    held_keys.clear();
+                }
+                if self.state == State::Playing {
+                    self.pause();
+                }
+            }
+
+            // remembered so the next window opens at the same size
+            if let Some(args) = e.resize_args() {
+                let size = [args.window_size[0] as u32, args.window_size[1] as u32];
+                if !self.settings.video.fullscreen && size[0] >= MIN_WINDOW_SIZE[0] && size[1] >= MIN_WINDOW_SIZE[1] {
+                    self.settings.video.width = size[0];
+                    self.settings.video.height = size[1];
+                }
+            }
+
+            if self.rebuild_window {
+                for held_keys in &mut self.held_keys {
+                    held_keys.clear();
+                }
+                return RunEnd::RebuildWindow;
+            }
+
+            if self.state == State::Quit {
+                window.set_should_close(true);
+            }
+
+            if let Some(u) = e.render_args() {
+                self.on_draw(&u, gl, glyph_cache, sprites);
+            }
+        }
+
+        if self.is_recording() {
+            self.toggle_recording();
+        }
+        RunEnd::Closed
+	}      
+
+    /// a key pressed or let go, for frontends that do not get their events from a piston window
+    pub fn key(&mut self, key: Key, is_press: bool) {
+        self.input(&Button::Keyboard(key), is_press);
+    }
+
+    fn gameobject_random_placement(&mut self, kind: EnemyKind, speed: f64) {
+        let rand_block: u32 = self.rng.gen_range(1, 4);
+        let mut randx: f64;
+        let mut randy: f64;
+
+        match rand_block {
+            1 => { //left
+                randx= self.rng.gen_range(45.0, self.dimensions[0]/4.0-20.0);
+                randy= self.rng.gen_range(40.0, self.dimensions[1]-135.0);
+            },
+            2 => { //top
+                randx= self.rng.gen_range(self.dimensions[0]/4.0+50.0, (self.dimensions[0]/4.0)*3.0);
+                randy= self.rng.gen_range(40.0, self.dimensions[1]/4.0);
+            },
+            3 => { //right
+                randx= self.rng.gen_range((self.dimensions[0]/4.0)*3.0+50.0, self.dimensions[0]-50.0);
+                randy= self.rng.gen_range(40.0, self.dimensions[1]-135.0);                
+            },
+            4 => { //bottom
+                randx= self.rng.gen_range(self.dimensions[0]/4.0+50.0, (self.dimensions[0]/4.0)*3.0);
+                randy= self.rng.gen_range((self.dimensions[1]/4.0)*3.0, self.dimensions[1]-135.0);                
+            },
+            _ => {
+                randx= self.rng.gen_range(45.0, self.dimensions[0]/4.0-20.0);
+                randy = self.rng.gen_range(40.0, self.dimensions[1]-135.0);            
+            }
+        }
+
+        //if enemy too close to where a player starts try again 
+        while self.near_entry(randx, randy) {
+            randx = self.rng.gen_range(45.0, self.dimensions[0]/4.0-10.0); 
+            randy = self.rng.gen_range(40.0, self.dimensions[1]-135.0); 
+        }
+        self.enemies.push(Enemy::with_kind(randx, randy, kind, speed));            
+    }
+
+    /// true when a point is too close to where a player comes into the room for a robot to be put there
+    fn near_entry(&self, x: f64, y: f64) -> bool {
+        (0..self.players.len().max(1)).any(|number| {
+            let (entry_x, entry_y) = match self.mode {
+                Mode::Versus => { let (x, y, _) = self.versus_entry(number); (x, y) },
+                _ => self.start_position(number),
+            };
+            (x - entry_x).abs() < 50.0 && (y - entry_y).abs() < 50.0
+        })
+    }
+
+    /// starts a new game of the given mode
+    fn start_game(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.hard_reset();
+    }
+
+    /// starts a new game from the first level and puts it in play
+    /// the mode stays the same as the last game
+    /// when the first room has intermission text the level banner is shown first
+    fn hard_reset(&mut self) {
+        self.level = 1;
+        self.room = self.mode.room(&self.campaign, self.level);
+        self.rooms_cleared = 0;
+        self.play_time = 0.0;
+        self.score = 0;
+        self.next_extra_life = 0;
+        self.versus_outcome = None;
+        self.notices.clear();
+        self.players = (0..self.player_count)
+            .map(|number| {
+                let (x, y) = self.start_position(number);
+                let mut player = Player::numbered(number, x, y);
+                // one hit is a kill in versus
+                if self.mode == Mode::Versus {
+                    player.max_health = 1;
+                    player.health = 1;
+                }
+                player
+            })
+            .collect();
+        self.enemies.clear();       
+        if self.room.intermission.is_empty() {
+            self.start_level();
+            self.state = State::Playing;
+        } else {
+            self.state = State::RoomTransition(ROOM_TRANSITION_TIME);
+        }
+    }    
+
+    /// where a player stands when a room starts, the second player just below the first
+    fn start_position(&self, number: usize) -> (f64, f64) {
+        (75.0, self.dimensions[1] / 2.0 + 45.0 * number as f64)
+    }
+
+    /// lays out the room, puts the players back at the entry and fills it with enemies for the current level
+    /// robots left behind in the last room do not follow the player
+    /// a player who lost every life in the last room comes back with full health
+    /// versus players come in from opposite sides of a room with the exit closed
+    fn start_level(&mut self) {
+        for number in 0..self.players.len() {
+            if self.mode == Mode::Versus {
+                self.respawn(number);
+            } else {
+                let (x, y) = self.start_position(number);
+                self.players[number].reset(x, y);
+            }
+            let player = &mut self.players[number];
+            player.is_moving = false;
+            player.bullets.clear(); 
+        }
+        self.enemy_bullets.clear();
+        self.enemies.clear();
+        self.walls.clear();
+        self.make_level_borders();
+        if self.room.exit == Exit::Cleared || self.mode == Mode::Versus {
+            let door = self.exit_door();
+            self.walls.push(door);
+        }
+        self.level_params = self.settings.gameplay.difficulty.level(self.level);
+        self.spawn_robots();
+    }
+
+    /// places the room's robots for the current level
+    fn spawn_robots(&mut self) {
+        let params = self.level_params;
+        for kind in self.room.robots.kinds(&params, &mut self.rng) {
+            self.gameobject_random_placement(kind, params.robot_speed);  
+        } 
+    }
+
+    /// seconds left before a timed campaign is won or a timed versus round ends
+    fn time_left(&self) -> Option<f64> {
+        if let Some(time_left) = self.joined_time_left() {
+            return Some(time_left)
+        }
+        let time = match (self.mode, self.campaign.win) {
+            (Mode::Versus, _) if self.settings.gameplay.versus_time > 0.0 => self.settings.gameplay.versus_time,
+            (Mode::Campaign, WinCondition::Time(time)) => time,
+            _ => return None,
+        };
+        Some((time - self.play_time).max(0.0))
+    }
+
+    /// true when the current room is done, either by leaving or by clearing it
+    fn room_finished(&self) -> bool {
+        match self.room.exit {
+            Exit::Top => self.check_win(),
+            Exit::Cleared => self.enemies.is_empty(),
+        }
+    }
+
+    /// how far the player has got in this game
+    fn progress(&self) -> Progress {
+        Progress {
+            rooms_finished: self.level - 1,
+            score: self.score,
+            time: self.play_time,
+        }
+    }
+
+    /// true when any player still in the game is standing in the exit at the top of the room
+    /// the exit can be used whether or not enemies are left
+    fn check_win(&self) -> bool {
+        self.players.iter().filter(|player| player.is_alive()).any(|player| {
+            player.pos.x > self.dimensions[0]/2.0-125.0 && 
+                player.pos.x < self.dimensions[0]/2.0+125.0 &&
+                player.pos.y > 5.0 && player.pos.y < 30.0
+        })
+    }
+
+    /// true when no player has a life left
+    fn all_players_out(&self) -> bool {
+        self.players.iter().all(|player| !player.is_alive())
+    }
+
+}
+
+#[cfg(test)] 
+mod berzerk_test {
+    use models::player::STARTING_HEALTH;
+    use super::*;
+
+    #[test]
+    fn test_new_game() {
+        let _g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+    } 
+
+    #[test]
+    fn test_game_walls() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        let t_walls = [1.0,1.0,2.0,2.0];
+        g.add_walls(t_walls,t_walls,t_walls,t_walls,t_walls,t_walls,t_walls,t_walls,t_walls);
+        assert!(g.walls.len() == 9); 
+    }
+
+    #[test]
+    fn test_game_states() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        assert!(g.state == State::Title);
+        g.hard_reset();
+        assert!(g.state == State::Playing);
+        assert!(g.enemies.len() == 6);
+    }
+
+    #[test]
+    fn test_restart_keeps_mode() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.start_game(Mode::Endless);
+        g.level = 9;
+        g.rooms_cleared = 8;
+        g.hard_reset();
+        assert!(g.mode == Mode::Endless);
+        assert!(g.level == 1 && g.rooms_cleared == 0);
+    }
+
+    #[test]
+    fn test_two_players() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.player_count = 2;
+        g.hard_reset();
+        assert!(g.players.len() == 2);
+        assert!(g.action_for(Key::W) == Some((1, Action::MoveUp)));
+        g.players[0].health = 0;
+        assert!(!g.all_players_out());
+        g.players[1].health = 0;
+        assert!(g.all_players_out());
+        g.start_level();
+        assert!(g.players.iter().all(|player| player.health == STARTING_HEALTH));
+    }
+
+    #[test]
+    fn test_attract_demo() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.set_player_count(2);
+        for _ in 0..(FPS * 30) {
+            g.tick(1.0 / FPS as f64);
+        }
+        assert!(g.demo.is_some() && g.players.len() == 1);
+        assert!(g.driver != Driver::Keys);
+        g.input(&Button::Keyboard(Key::Space), true);
+        assert!(g.state == State::Title && g.demo.is_none());
+        assert!(g.player_count == 2 && g.driver == Driver::Keys);
+    }
+
+    #[test]
+    fn test_room_clear_bonus() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.player_count = 2;
+        g.hard_reset();
+        g.enemies.clear();
+        g.score = 900;
+        g.players[0].score = 500;
+        g.players[0].pos.x = g.dimensions[0] / 2.0;
+        g.players[0].pos.y = 20.0;
+        g.playing_update(&UpdateArgs { dt: 1.0 / FPS as f64 });
+        assert!(g.state == State::RoomTransition(ROOM_TRANSITION_TIME));
+        assert!(g.score == 1100);
+        assert!(g.players[0].score == 700 && g.players[1].score == 200);
+        assert!(g.players.iter().all(|player| player.max_health == STARTING_HEALTH + 1));
+    }
+
+    #[test]
+    fn test_versus_respawn() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.player_count = 2;
+        g.start_game(Mode::Versus);
+        assert!(g.walls.len() == 10);
+        assert!(g.players[1].pos.x == g.dimensions[0] - 75.0 && g.players[1].dir == Direction::WEST);
+        g.players[1].pos.x = 300.0;
+        g.players[1].lose_life(Cause::PlayerBullet);
+        g.players[0].kills = 1;
+        g.versus_update();
+        assert!(g.players[1].is_alive() && g.players[1].pos.x == g.dimensions[0] - 75.0);
+        g.players[0].kills = g.settings.gameplay.versus_kills;
+        g.versus_update();
+        assert!(g.state == State::Won);
+        assert!(g.versus_banner() == "PLAYER 1 WINS");
+    }
+
+    #[test]
+    fn test_versus_kill_limit_reached_together() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.player_count = 2;
+        g.start_game(Mode::Versus);
+        let limit = g.settings.gameplay.versus_kills;
+        g.players[0].kills = limit - 1;
+        g.players[1].kills = limit - 1;
+        // each player's bullet is about to reach the other, so both are hit in the same update
+        let (x0, y0, x1, y1) = (g.players[0].pos.x, g.players[0].pos.y, g.players[1].pos.x, g.players[1].pos.y);
+        g.players[0].bullets.push(Bullet::new(x1 - 22.0, y1, Direction::EAST));
+        g.players[1].bullets.push(Bullet::new(x0 + 22.0, y0, Direction::WEST));
+        g.on_update(&UpdateArgs { dt: 1.0 / FPS as f64 });
+        assert!(g.players[0].kills == limit && g.players[1].kills == limit);
+        assert!(g.state == State::Won && g.versus_outcome == Some(Outcome::Draw));
+        assert!(g.versus_banner() == "DRAW");
+    }
+
+    #[test]
+    fn test_silent_game() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        assert!(g.volume() == g.settings.audio.volume);
+        g.set_silent(true);
+        assert!(g.volume() == 0.0);
+        assert!(g.settings.audio.sound_on);
+        g.set_silent(false);
+        g.settings.audio.sound_on = false;
+        assert!(g.volume() == 0.0);
+    }
+
+}
@@ -0,0 +1,63 @@
+use campaign::{Campaign, Progress, Room};
+
+/// which kind of game was started from the title screen
+/// Campaign: the rooms of the loaded campaign, won by meeting its win condition
+/// Endless: rooms keep coming until the player runs out of lives,
+/// the score and the number of rooms cleared are the only goals
+/// Versus: two players fight in one closed room with the robots in the way,
+/// decided by the versus rules instead of the campaign
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Mode {
+    Campaign,
+    Endless,
+    Versus,
+}
+
+impl Mode {
+    /// true when the progress wins the game
+    pub fn is_won(&self, campaign: &Campaign, progress: &Progress) -> bool {
+        match *self {
+            Mode::Campaign => campaign.is_won(progress),
+            Mode::Endless | Mode::Versus => false,
+        }
+    }
+
+    /// the room played on a level, endless and versus rooms always come from the difficulty preset
+    pub fn room(&self, campaign: &Campaign, level: u32) -> Room {
+        match *self {
+            Mode::Campaign => campaign.room(level).clone(),
+            Mode::Endless | Mode::Versus => Room::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use campaign::Exit;
+
+    fn progress(rooms_finished: u32) -> Progress {
+        Progress { rooms_finished, score: 0, time: 0.0 }
+    }
+
+    #[test]
+    fn test_campaign_won_after_last_level() {
+        let campaign = Campaign::default();
+        assert!(!Mode::Campaign.is_won(&campaign, &progress(3)));
+        assert!(Mode::Campaign.is_won(&campaign, &progress(4)));
+    }
+
+    #[test]
+    fn test_endless_never_won() {
+        let campaign = Campaign::default();
+        assert!(!Mode::Endless.is_won(&campaign, &progress(4)));
+        assert!(!Mode::Endless.is_won(&campaign, &progress(u32::MAX)));
+    }
+
+    #[test]
+    fn test_endless_ignores_campaign_rooms() {
+        let campaign = Campaign::parse("[room]\nsoldiers = 1\nexit = cleared\n").unwrap();
+        assert!(Mode::Campaign.room(&campaign, 1).exit == Exit::Cleared);
+        assert!(Mode::Endless.room(&campaign, 1) == Room::default());
+    }
+}
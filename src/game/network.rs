@@ -0,0 +1,464 @@
+use piston::input::*;
+use std::collections::VecDeque;
+
+use controls::Action;
+use models::bullet::Bullet;
+use models::enemy::Enemy;
+use models::player::Player;
+use net::{BulletState, ClientMessage, Connection, EnemyState, PlayerState, Screen, Snapshot};
+use render::Renderer;
+
+use super::versus::Outcome;
+use super::{Direction, Driver, Game, Mode, State};
+
+/// the player the joining game controls
+pub const REMOTE_PLAYER: usize = 1;
+/// moves kept waiting for the host to use, older ones are dropped so the joining player never lags far behind
+const MAX_QUEUED_MOVES: usize = 30;
+
+/// the game running the simulation for both players
+/// moves: the joining player's moves that arrived and have not been used yet, one is used each update
+/// remote_dir: the direction held on the last move used
+/// ack: the seq of that move, sent back so the joining game knows which moves were used
+/// tick: counts the updates, sent with every snapshot
+pub struct HostSession {
+    connection: Connection,
+    moves: VecDeque<(u64, Option<Direction>)>,
+    remote_dir: Option<Direction>,
+    ack: u64,
+    tick: u64,
+}
+
+/// the game that joined, it draws the host's snapshots and moves its own player
+/// right away instead of waiting for the host to send it back
+/// seq: the number of the last move sent
+/// pending: moves the host has not used yet, played again on top of each snapshot
+/// last_tick: the tick of the newest snapshot, older ones are ignored
+/// screen: what the host is showing
+/// time_left: the host's time left in a timed game
+pub struct ClientSession {
+    connection: Connection,
+    seq: u64,
+    pending: Vec<(u64, Option<Direction>)>,
+    last_tick: u64,
+    screen: Screen,
+    time_left: Option<f64>,
+}
+
+pub enum Network {
+    Host(HostSession),
+    Client(ClientSession),
+}
+
+/// moves a player one update as if the direction was held, the same way playing_update does
+fn predict(player: &mut Player, dir: Option<Direction>) {
+    match dir {
+        Some(dir) if player.is_alive() => {
+            player.is_moving = true;
+            player.dir = dir;
+        },
+        _ => player.is_moving = false,
+    }
+    player.update();
+}
+
+impl Game {
+    /// runs the game for a player who joined over the network as the second player
+    pub fn host(&mut self, connection: Connection) {
+        self.network = Some(Network::Host(HostSession {
+            connection,
+            moves: VecDeque::new(),
+            remote_dir: None,
+            ack: 0,
+            tick: 0,
+        }));
+        self.set_player_count(2);
+    }
+
+    /// shows a game run by a host, playing as the second player
+    pub fn join(&mut self, connection: Connection) {
+        self.network = Some(Network::Client(ClientSession {
+            connection,
+            seq: 0,
+            pending: Vec::new(),
+            last_tick: 0,
+            screen: Screen::Waiting,
+            time_left: None,
+        }));
+        self.set_player_count(2);
+        // the host never sends its walls, the room is laid out the same on both sides
+        self.walls.clear();
+        self.make_level_borders();
+        self.state = State::Joined;
+    }
+
+    /// how many players use this keyboard
+    pub(super) fn local_players(&self) -> usize {
+        if self.network.is_some() { 1 } else { self.held_keys.len() }
+    }
+
+    /// the direction a player wants to move in, from the network for the joining player
+    /// and from the program driving the first player when there is one
+    pub(super) fn steering(&self, number: usize) -> Option<Direction> {
+        if let (0, Driver::Agent(dir)) = (number, self.driver) {
+            return dir
+        }
+        match self.network {
+            Some(Network::Host(ref host)) if number == REMOTE_PLAYER => host.remote_dir,
+            _ => self.held_keys.get(number).and_then(|held_keys| held_keys.direction()),
+        }
+    }
+
+    /// the time left sent by the host, none when this game is not joined to one
+    pub(super) fn joined_time_left(&self) -> Option<f64> {
+        match self.network {
+            Some(Network::Client(ref client)) => client.time_left,
+            _ => None,
+        }
+    }
+
+    /// what the joining game should show for the current state
+    fn screen(&self) -> Screen {
+        match self.state {
+            State::Playing => Screen::Playing,
+            State::Paused => Screen::Paused,
+            State::RoomTransition(_) => Screen::Transition,
+            State::GameOver => Screen::GameOver,
+            State::Won => Screen::Won,
+            _ => Screen::Waiting,
+        }
+    }
+
+    /// the host uses the joining player's moves and shots before the update
+    pub(super) fn host_receive(&mut self) {
+        let mut host = match self.network.take() {
+            Some(Network::Host(host)) => host,
+            network => {
+                self.network = network;
+                return
+            },
+        };
+        let messages = host.connection.receive();
+        self.host_handle(&mut host, &messages);
+        if host.connection.is_closed() {
+            self.remote_left();
+        } else {
+            self.network = Some(Network::Host(host));
+        }
+    }
+
+    /// the joining player's character leaves with them instead of being handed to this keyboard,
+    /// and later games are for one player
+    /// a versus match can not go on alone, so it ends with the player who stayed
+    fn remote_left(&mut self) {
+        self.notify("PLAYER 2 LEFT".to_string());
+        self.players.truncate(REMOTE_PLAYER);
+        self.set_player_count(1);
+        if self.mode == Mode::Versus && (self.state == State::Playing || self.state == State::Paused) {
+            self.end_versus(Outcome::Winner(0));
+        }
+    }
+
+    /// fires the joining player's shots and queues their moves, then takes the next move for this update
+    /// the joining game sends a move every update, so when two arrive together the second waits
+    /// for the next update, that way every move acked has really been made
+    fn host_handle(&mut self, host: &mut HostSession, messages: &[String]) {
+        for message in messages {
+            match ClientMessage::parse(message) {
+                Some(ClientMessage::Move { seq, dir }) => {
+                    host.moves.push_back((seq, dir));
+                    if host.moves.len() > MAX_QUEUED_MOVES {
+                        host.moves.pop_front();
+                    }
+                },
+                Some(ClientMessage::Fire { .. }) if self.state == State::Playing => self.fire(REMOTE_PLAYER),
+                Some(ClientMessage::Fire { .. }) => (),
+                // the handshake checked both games speak the same version, so this is noise
+                None => (),
+            }
+        }
+        if let Some((seq, dir)) = host.moves.pop_front() {
+            host.remote_dir = dir;
+            host.ack = seq;
+        }
+    }
+
+    /// the host sends everything the joining game needs to draw after every update
+    pub(super) fn host_send(&mut self) {
+        let snapshot = match self.network {
+            Some(Network::Host(ref host)) => self.snapshot(host.tick + 1, host.ack),
+            _ => return,
+        };
+        if let Some(Network::Host(ref mut host)) = self.network {
+            host.tick = snapshot.tick;
+            host.connection.send(&snapshot.to_line());
+        }
+    }
+
+    pub(super) fn snapshot(&self, tick: u64, ack: u64) -> Snapshot {
+        let mut bullets = Vec::new();
+        for (owner, player) in self.players.iter().enumerate() {
+            for bullet in &player.bullets {
+                let (x, y) = bullet.pos();
+                bullets.push(BulletState { owner: Some(owner), x, y, dir: bullet.dir(), speed: bullet.speed() });
+            }
+        }
+        for bullet in &self.enemy_bullets {
+            let (x, y) = bullet.pos();
+            bullets.push(BulletState { owner: None, x, y, dir: bullet.dir(), speed: bullet.speed() });
+        }
+        Snapshot {
+            tick,
+            ack,
+            screen: self.screen(),
+            mode: self.mode,
+            time_left: self.time_left(),
+            level: self.level,
+            score: self.score,
+            players: self.players.iter().map(|player| PlayerState {
+                x: player.pos.x,
+                y: player.pos.y,
+                dir: player.dir,
+                health: player.health,
+                max_health: player.max_health,
+                is_moving: player.is_moving,
+                score: player.score,
+                kills: player.kills,
+            }).collect(),
+            enemies: self.enemies.iter().map(|enemy| EnemyState {
+                x: enemy.pos.x,
+                y: enemy.pos.y,
+                dir: enemy.dir,
+                kind: enemy.kind,
+            }).collect(),
+            bullets,
+        }
+    }
+
+    /// replaces the room with the host's
+    pub(super) fn apply_snapshot(&mut self, snapshot: &Snapshot) {
+        self.mode = snapshot.mode;
+        self.level = snapshot.level;
+        self.score = snapshot.score;
+        self.players = snapshot.players.iter().enumerate().map(|(number, state)| {
+            let mut player = Player::numbered(number, state.x, state.y);
+            player.dir = state.dir;
+            player.health = state.health;
+            player.max_health = state.max_health;
+            player.is_moving = state.is_moving;
+            player.score = state.score;
+            player.kills = state.kills;
+            player
+        }).collect();
+        self.enemies = snapshot.enemies.iter().map(|state| {
+            let mut enemy = Enemy::with_kind(state.x, state.y, state.kind, 0.0);
+            enemy.dir = state.dir;
+            enemy
+        }).collect();
+        self.enemy_bullets.clear();
+        for state in &snapshot.bullets {
+            let bullet = Bullet::with_speed(state.x, state.y, state.dir, state.speed);
+            match state.owner {
+                Some(owner) if owner < self.players.len() => self.players[owner].bullets.push(bullet),
+                _ => self.enemy_bullets.push(bullet),
+            }
+        }
+    }
+
+    /// the joining game's fire key is sent to the host, nothing else is
+    pub(super) fn joined_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let Button::Keyboard(key) = *button {
+            if let Some((_, Action::Fire)) = self.action_for(key) {
+                if let Some(Network::Client(ref mut client)) = self.network {
+                    client.connection.send(&ClientMessage::Fire { seq: client.seq }.to_line());
+                }
+            }
+        }
+    }
+
+    /// shows the newest snapshot with the moves the host has not used yet played on top,
+    /// then sends this update's move and makes it straight away
+    pub(super) fn joined_update(&mut self, _args: &UpdateArgs) {
+        let mut client = match self.network.take() {
+            Some(Network::Client(client)) => client,
+            network => {
+                self.network = network;
+                return
+            },
+        };
+        let newest = client.connection.receive().iter().rev()
+            .filter_map(|message| Snapshot::parse(message))
+            .next();
+        if let Some(snapshot) = newest {
+            if snapshot.tick > client.last_tick {
+                client.last_tick = snapshot.tick;
+                client.screen = snapshot.screen;
+                client.time_left = snapshot.time_left;
+                self.apply_snapshot(&snapshot);
+                client.pending.retain(|&(seq, _)| seq > snapshot.ack);
+                if let Some(player) = self.players.get_mut(REMOTE_PLAYER) {
+                    for &(_, dir) in &client.pending {
+                        predict(player, dir);
+                    }
+                }
+            }
+        }
+        if client.connection.is_closed() {
+            self.host_left();
+            return
+        }
+
+        let dir = self.steering(0);
+        client.seq += 1;
+        client.connection.send(&ClientMessage::Move { seq: client.seq, dir }.to_line());
+        if client.screen == Screen::Playing {
+            client.pending.push((client.seq, dir));
+            if let Some(player) = self.players.get_mut(REMOTE_PLAYER) {
+                predict(player, dir);
+            }
+        }
+        self.network = Some(Network::Client(client));
+    }
+
+    /// without the host there is nothing to show, so the game goes back to the title on its own
+    fn host_left(&mut self) {
+        self.notify("LOST THE CONNECTION TO THE HOST".to_string());
+        self.set_player_count(1);
+        self.state = State::Title;
+    }
+
+    pub(super) fn joined_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        let screen = match self.network {
+            Some(Network::Client(ref client)) => client.screen,
+            _ => Screen::Waiting,
+        };
+        match screen {
+            Screen::Waiting => self.draw_banner("WAITING FOR THE HOST", 0.0, renderer),
+            Screen::Playing => self.draw_room(renderer),
+            Screen::Paused => {
+                self.draw_room(renderer);
+                self.draw_banner("PAUSED", -100.0, renderer);
+            },
+            Screen::Transition => {
+                let banner = format!("LEVEL {}", self.level);
+                self.draw_banner(banner.as_str(), 0.0, renderer);
+            },
+            Screen::GameOver => {
+                self.draw_room(renderer);
+                self.draw_banner("GAME OVER", 0.0, renderer);
+            },
+            Screen::Won => {
+                self.draw_room(renderer);
+                let banner = if self.mode == Mode::Versus { self.versus_banner() } else { "CONGRATS YOU WON".to_string() };
+                self.draw_banner(&banner, 0.0, renderer);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use std::net::TcpListener;
+    use std::thread;
+    use campaign::Campaign;
+    use game::Mode;
+    use highscores::HighScores;
+    use settings::Settings;
+    use super::*;
+
+    /// both ends of a connection over the loopback address
+    fn connected() -> (Connection, Connection) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let joining = thread::spawn(move || Connection::join(address).unwrap());
+        let hosting = Connection::accept(&listener).unwrap();
+        (hosting, joining.join().unwrap())
+    }
+
+    #[test]
+    fn test_host_uses_one_move_per_update() {
+        let (hosting, _joining) = connected();
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.host(hosting);
+        let mut host = match g.network.take() {
+            Some(Network::Host(host)) => host,
+            _ => panic!("not hosting"),
+        };
+        let moves = vec![
+            ClientMessage::Move { seq: 1, dir: Some(Direction::NORTH) }.to_line(),
+            ClientMessage::Move { seq: 2, dir: Some(Direction::EAST) }.to_line(),
+        ];
+        g.host_handle(&mut host, &moves);
+        assert!(host.ack == 1 && host.remote_dir == Some(Direction::NORTH));
+        g.host_handle(&mut host, &[]);
+        assert!(host.ack == 2 && host.remote_dir == Some(Direction::EAST));
+        g.host_handle(&mut host, &[]);
+        assert!(host.ack == 2 && host.remote_dir == Some(Direction::EAST));
+    }
+
+    #[test]
+    fn test_remote_player_leaves() {
+        let (hosting, joining) = connected();
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.host(hosting);
+        g.start_game(Mode::Campaign);
+        assert!(g.players.len() == 2);
+        drop(joining);
+        for _ in 0..100 {
+            g.on_update(&UpdateArgs { dt: 1.0 / 60.0 });
+            if g.network.is_none() {
+                break
+            }
+            thread::sleep(::std::time::Duration::from_millis(10));
+        }
+        assert!(g.network.is_none());
+        assert!(g.players.len() == 1 && g.player_count == 1);
+    }
+
+    #[test]
+    fn test_host_leaves() {
+        let (hosting, joining) = connected();
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.join(joining);
+        drop(hosting);
+        for _ in 0..100 {
+            g.on_update(&UpdateArgs { dt: 1.0 / 60.0 });
+            if g.network.is_none() {
+                break
+            }
+            thread::sleep(::std::time::Duration::from_millis(10));
+        }
+        assert!(g.network.is_none());
+        assert!(g.state == State::Title && g.player_count == 1);
+        assert!(g.notices.len() == 1);
+    }
+
+    #[test]
+    fn test_replay_matches_prediction() {
+        // the joining game predicts two moves, the host only used the first when the snapshot was made
+        let moves = [(1, Some(Direction::NORTH)), (2, Some(Direction::EAST))];
+        let mut predicted = Player::new(100.0, 100.0);
+        for &(_, dir) in moves.iter() {
+            predict(&mut predicted, dir);
+        }
+
+        let mut host = Player::new(100.0, 100.0);
+        predict(&mut host, moves[0].1);
+        let mut replayed = Player::new(host.pos.x, host.pos.y);
+        for &(_, dir) in moves.iter().filter(|&&(seq, _)| seq > 1) {
+            predict(&mut replayed, dir);
+        }
+        assert!(replayed.pos.x == predicted.pos.x && replayed.pos.y == predicted.pos.y);
+    }
+
+    #[test]
+    fn test_dead_player_stays_still() {
+        let mut player = Player::new(100.0, 100.0);
+        player.health = 0;
+        predict(&mut player, Some(Direction::NORTH));
+        assert!(player.pos.y == 100.0 && !player.is_moving);
+    }
+}
@@ -0,0 +1,57 @@
+use render::Renderer;
+
+use super::Game;
+
+/// how long a notice stays on screen in seconds
+const NOTICE_TIME: f64 = 2.0;
+
+/// a message shown over the screen for a short time
+/// message: the text to show
+/// time_left: seconds before it disappears
+pub struct Notice {
+    message: String,
+    time_left: f64,
+}
+
+impl Game {
+    /// shows a message on screen for a couple of seconds, whatever is being shown
+    pub(super) fn notify(&mut self, message: String) {
+        self.notices.push(Notice {
+            message,
+            time_left: NOTICE_TIME,
+        });
+    }
+
+    /// counts down the notices and drops the ones that ran out
+    pub(super) fn tick_notices(&mut self, dt: f64) {
+        for notice in &mut self.notices {
+            notice.time_left -= dt;
+        }
+        self.notices.retain(|notice| notice.time_left > 0.0);
+    }
+
+    /// stacks the notices under the top wall, over every screen
+    pub(super) fn draw_notices<R: Renderer>(&self, renderer: &mut R) {
+        for (i, notice) in self.notices.iter().enumerate() {
+            renderer.text(self.settings.video.text_color, 32, notice.message.as_str(), self.dimensions[0]/2.0-95.0, 80.0 + 35.0 * i as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use settings::Settings;
+    use highscores::HighScores;
+    use campaign::Campaign;
+
+    #[test]
+    fn test_notices_run_out() {
+        let mut g = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        g.notify("COULD NOT SAVE SETTINGS".to_string());
+        g.tick_notices(NOTICE_TIME / 2.0);
+        assert!(g.notices.len() == 1);
+        g.tick_notices(NOTICE_TIME);
+        assert!(g.notices.is_empty());
+    }
+}
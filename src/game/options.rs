@@ -0,0 +1,124 @@
+use piston::input::*;
+
+use render::Renderer;
+use settings::{self, Settings, WINDOW_SIZES};
+
+use super::menu::Menu;
+use super::{Game, State};
+
+const SOUND: usize = 0;
+const VOLUME: usize = 1;
+const DIFFICULTY: usize = 2;
+const FRIENDLY_FIRE: usize = 3;
+const WALL_COLOUR: usize = 4;
+const TEXT_COLOUR: usize = 5;
+const WINDOW_SIZE: usize = 6;
+const FULLSCREEN: usize = 7;
+const CONTROLS: usize = 8;
+const SAVE_AND_BACK: usize = 9;
+
+/// how much left and right change the volume by
+const VOLUME_STEP: f32 = 0.05;
+
+/// the text for each option showing its current value
+fn options_items(settings: &Settings) -> Vec<String> {
+    vec![
+        if settings.audio.sound_on { "SOUND ON".to_string() } else { "SOUND OFF".to_string() },
+        format!("VOLUME {}%", (settings.audio.volume * 100.0).round()),
+        format!("DIFFICULTY {}", settings.gameplay.difficulty.name().to_uppercase()),
+        if settings.gameplay.friendly_fire { "FRIENDLY FIRE ON".to_string() } else { "FRIENDLY FIRE OFF".to_string() },
+        format!("WALL COLOUR {}", settings::color_name(settings.video.wall_color)),
+        format!("TEXT COLOUR {}", settings::color_name(settings.video.text_color)),
+        format!("WINDOW {}X{}", settings.video.width, settings.video.height),
+        if settings.video.fullscreen { "FULLSCREEN ON".to_string() } else { "FULLSCREEN OFF".to_string() },
+        "CONTROLS".to_string(),
+        "SAVE AND BACK".to_string(),
+    ]
+}
+
+pub fn options_menu(settings: &Settings) -> Menu {
+    let items = options_items(settings);
+    let items: Vec<&str> = items.iter().map(|item| item.as_str()).collect();
+    Menu::new(&items)
+}
+
+impl Game {
+    /// opens the options menu, going back to the given state when it is closed
+    pub(super) fn open_options(&mut self, back_to: State) {
+        self.options_return = back_to;
+        self.options_menu.reset();
+        self.state = State::Options;
+    }
+
+    fn refresh_options_menu(&mut self) {
+        for (i, item) in options_items(&self.settings).iter().enumerate() {
+            self.options_menu.set_item(i, item.as_str());
+        }
+    }
+
+    /// enter or space changes the highlighted option, left and right change the volume
+    /// changes are used right away and are saved when leaving
+    pub(super) fn options_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let Button::Keyboard(key) = *button {
+            if key == Key::Left || key == Key::Right {
+                if self.options_menu.highlighted() == VOLUME {
+                    let step = if key == Key::Left { -VOLUME_STEP } else { VOLUME_STEP };
+                    self.settings.audio.volume = (self.settings.audio.volume + step).clamp(0.0, 1.0);
+                    self.refresh_options_menu();
+                }
+                return
+            }
+            match self.options_menu.navigate(key) {
+                Some(SOUND) => {
+                    self.settings.audio.sound_on = !self.settings.audio.sound_on;
+                },
+                Some(VOLUME) => {
+                    let volume = self.settings.audio.volume + VOLUME_STEP;
+                    self.settings.audio.volume = if volume > 1.0 { 0.0 } else { volume };
+                },
+                Some(DIFFICULTY) => {
+                    self.settings.gameplay.difficulty = self.settings.gameplay.difficulty.next();
+                },
+                Some(FRIENDLY_FIRE) => {
+                    self.settings.gameplay.friendly_fire = !self.settings.gameplay.friendly_fire;
+                },
+                Some(WALL_COLOUR) => {
+                    self.settings.video.wall_color = settings::next_color(self.settings.video.wall_color);
+                },
+                Some(TEXT_COLOUR) => {
+                    self.settings.video.text_color = settings::next_color(self.settings.video.text_color);
+                },
+                Some(WINDOW_SIZE) => {
+                    let current = [self.settings.video.width, self.settings.video.height];
+                    let next = match WINDOW_SIZES.iter().position(|size| *size == current) {
+                        Some(i) => WINDOW_SIZES[(i + 1) % WINDOW_SIZES.len()],
+                        None => WINDOW_SIZES[0],
+                    };
+                    self.settings.video.width = next[0];
+                    self.settings.video.height = next[1];
+                    self.rebuild_window = true;
+                },
+                Some(FULLSCREEN) => self.toggle_fullscreen(),
+                Some(CONTROLS) => self.open_controls(),
+                Some(SAVE_AND_BACK) => {
+                    self.save_settings_from_menu();
+                    self.state = self.options_return;
+                },
+                _ => (),
+            }
+            self.refresh_options_menu();
+        }
+    }
+
+    /// the game stays frozen behind the options
+    pub(super) fn options_update(&mut self, _args: &UpdateArgs) {
+    }
+
+    pub(super) fn options_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_banner("OPTIONS", -250.0, renderer);
+        self.options_menu.draw(self.dimensions[0]/2.0-200.0, self.dimensions[1]/2.0-200.0, renderer);
+    }
+}
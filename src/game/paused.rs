@@ -0,0 +1,62 @@
+use piston::input::*;
+
+use controls::Action;
+use render::Renderer;
+
+use super::{Game, State, FPS};
+
+/// the choices on the pause menu in the order they are listed
+pub const PAUSE_ITEMS: [&str; 4] = ["RESUME", "RESTART", "OPTIONS", "QUIT"];
+const RESUME: usize = 0;
+const RESTART: usize = 1;
+const OPTIONS: usize = 2;
+const QUIT: usize = 3;
+
+impl Game {
+    /// freezes the game and opens the pause menu
+    pub(super) fn pause(&mut self) {
+        for player in &mut self.players {
+            player.is_moving = false;
+        }
+        self.pause_menu.reset();
+        self.state = State::Paused;
+    }
+
+    pub(super) fn paused_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let Button::Keyboard(key) = *button {
+            match (self.action_for(key).map(|(_, action)| action), key) {
+                (Some(Action::Pause), _) => self.state = State::Playing,
+                // debug builds can run the game one update at a time to look at collisions
+                (_, Key::F10) if cfg!(debug_assertions) => {
+                    self.playing_update(&UpdateArgs { dt: 1.0 / FPS as f64 });
+                    if self.state == State::Playing {
+                        self.state = State::Paused;
+                    }
+                },
+                (_, _) => match self.pause_menu.navigate(key) {
+                    Some(RESUME) => self.state = State::Playing,
+                    Some(RESTART) => self.hard_reset(),
+                    Some(OPTIONS) => self.open_options(State::Paused),
+                    Some(QUIT) => self.state = State::Quit,
+                    _ => (),
+                },
+            }
+        }
+    }
+
+    /// nothing moves while paused
+    pub(super) fn paused_update(&mut self, _args: &UpdateArgs) {
+    }
+
+    pub(super) fn paused_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_room(renderer);
+        self.draw_banner("PAUSED", -100.0, renderer);
+        self.pause_menu.draw(self.dimensions[0]/2.0-95.0, self.dimensions[1]/2.0-50.0, renderer);
+        if cfg!(debug_assertions) {
+            self.draw_banner("F10 TO STEP", 150.0, renderer);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use piston::input::*;
+
+use controls::Action;
+use models::bullet::Bullet;
+use render::Renderer;
+
+use super::transition::ROOM_TRANSITION_TIME;
+use super::{Direction, Game, Mode, State, FIRE_COOLDOWN};
+
+impl Game {
+    pub(super) fn playing_input(&mut self, button: &Button, is_press: bool) {
+        // movement keys are tracked in held_keys and applied in playing_update
+        if is_press {
+            if let Button::Keyboard(key) = *button {
+                match self.action_for(key) {
+                    Some((number, Action::Fire)) => {
+                        self.fire(number);
+                    },
+
+                    Some((_, Action::Pause)) => {
+                        self.pause();
+                    },
+
+                    Some((_, Action::Restart)) => {
+                        self.hard_reset();
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// a shot from a player who is still in the room
+    pub(super) fn fire(&mut self, number: usize) {
+        if self.players.get(number).is_some_and(|player| player.is_alive()) {
+            if self.fire_cooldown <= 0.0 {
+                self.play_sound(0);
+                self.fire_cooldown = FIRE_COOLDOWN; 
+            }
+            let player = &mut self.players[number];
+            let bullet = Bullet::new(player.pos.x, player.pos.y, player.dir);
+            player.bullets.push(bullet); 
+        }
+    }
+
+    /// points each player in the direction of their most recent held movement key
+    fn steer_players(&mut self) {
+        let dirs: Vec<Option<Direction>> = (0..self.players.len()).map(|number| self.steering(number)).collect();
+        for (player, dir) in self.players.iter_mut().zip(dirs) {
+            match dir {
+                Some(dir) if player.is_alive() => {
+                    player.is_moving = true;
+                    player.dir = dir;
+                },
+                _ => player.is_moving = false,
+            }
+        }
+    }
+
+    pub(super) fn playing_update(&mut self, args: &UpdateArgs) {
+        self.steer_players();
+        for player in &mut self.players {
+            player.update();
+        }
+        self.player_bullet_check();
+        self.enemy_bullet_check();
+        self.tick_cooldown(args);
+
+        for player in &mut self.players {
+            player.bullets.retain(|b| b.alive); 
+        }
+        self.enemy_bullets.retain(|b| b.alive);         
+        self.enemies.retain(|enemy| enemy.alive);
+
+        self.enemy_chance_shoot();
+        self.enemy_update();
+        self.wall_update();
+        self.play_time += args.dt;
+
+        if self.mode == Mode::Versus {
+            self.versus_update();
+            return
+        }
+
+        if self.all_players_out() {
+            if self.fire_cooldown <= 0.0 {
+               self.play_sound(4); 
+               self.fire_cooldown = FIRE_COOLDOWN;
+            }
+            self.end_game(false);
+            return
+        }   
+
+        if self.room_finished() {
+            if self.enemies.is_empty() {
+                self.rooms_cleared += 1;
+                self.award_room_clear();
+            } else {
+                self.taunt();
+            }
+            self.level = self.level.saturating_add(1);
+            self.room = self.mode.room(&self.campaign, self.level);
+            self.state = State::RoomTransition(ROOM_TRANSITION_TIME);
+        }
+        self.award_extra_lives();
+
+        if self.mode.is_won(&self.campaign, &self.progress()) {
+            self.end_game(true);
+        }
+    }
+
+    /// the robots left behind call after a player who runs from the room
+    fn taunt(&mut self) {
+        self.notify("CHICKEN! FIGHT LIKE A ROBOT!".to_string());
+        self.play_sound(5);
+    }
+
+    pub(super) fn playing_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_room(renderer);
+        self.demo_draw(renderer);
+    }
+}
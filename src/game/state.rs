@@ -0,0 +1,31 @@
+use controls::Action;
+
+/// the screen the game is currently on
+/// each state has its own input, update and draw functions in a file of the same name
+/// Title: waiting for the player to start a game
+/// Playing: the simulation is running
+/// Paused: the simulation is frozen and the pause menu is showing
+/// Options: the options menu opened from the title or pause menu
+/// Controls: the list of actions and the keys bound to them
+/// Rebind: waiting for a key to bind to the action
+/// RoomTransition: the room was exited, holds the seconds left before the next room starts
+/// EnterInitials: the score made the high score table, won is where to go after
+/// GameOver: the player ran out of lives
+/// Won: the player made it through every level
+/// Joined: showing a game run by another player over the network
+/// Quit: the player chose to quit, the window closes on the next event
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum State {
+    Title,
+    Playing,
+    Paused,
+    Options,
+    Controls,
+    Rebind(Action),
+    RoomTransition(f64),
+    EnterInitials { won: bool },
+    GameOver,
+    Won,
+    Joined,
+    Quit,
+}
@@ -0,0 +1,58 @@
+use piston::input::*;
+use render::Renderer;
+
+use super::{Game, Mode, State, MAX_PLAYERS};
+
+/// the choices on the title menu in the order they are listed
+pub const TITLE_ITEMS: [&str; 6] = ["START", "ENDLESS", "VERSUS", "PLAYERS 1", "OPTIONS", "QUIT"];
+const START: usize = 0;
+const ENDLESS: usize = 1;
+const VERSUS: usize = 2;
+const PLAYERS: usize = 3;
+const OPTIONS: usize = 4;
+const QUIT: usize = 5;
+
+impl Game {
+    pub(super) fn title_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let Button::Keyboard(key) = *button {
+            match self.title_menu.navigate(key) {
+                Some(START) => self.start_game(Mode::Campaign),
+                Some(ENDLESS) => self.start_game(Mode::Endless),
+                // versus always needs both players
+                Some(VERSUS) => {
+                    self.set_player_count(MAX_PLAYERS);
+                    self.start_game(Mode::Versus);
+                },
+                // a network game always has two players
+                Some(PLAYERS) if self.network.is_none() => {
+                    let count = self.player_count % MAX_PLAYERS + 1;
+                    self.set_player_count(count);
+                },
+                Some(OPTIONS) => self.open_options(State::Title),
+                Some(QUIT) => self.state = State::Quit,
+                _ => (),
+            }
+        }
+    }
+
+    /// changes how many players the next game has
+    pub(super) fn set_player_count(&mut self, count: usize) {
+        self.player_count = count;
+        let label = format!("PLAYERS {}", count);
+        self.title_menu.set_item(PLAYERS, label.as_str());
+    }
+
+    pub(super) fn title_update(&mut self, args: &UpdateArgs) {
+        self.tick_cooldown(args);
+        self.title_idle(args.dt);
+    }
+
+    pub(super) fn title_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_banner("BERZERK", -100.0, renderer);
+        self.title_menu.draw(self.dimensions[0]/2.0-95.0, self.dimensions[1]/2.0-50.0, renderer);
+        self.draw_high_scores(60.0, 80.0, renderer);
+    }
+}
@@ -0,0 +1,33 @@
+use piston::input::*;
+use render::Renderer;
+
+use super::{Game, State};
+
+/// how long the level banner shows between rooms in seconds
+pub const ROOM_TRANSITION_TIME: f64 = 1.5;
+
+impl Game {
+    /// movement keys are ignored until the next room starts
+    pub(super) fn transition_input(&mut self, _button: &Button, _is_press: bool) {
+    }
+
+    /// counts down and then fills the next room
+    pub(super) fn transition_update(&mut self, args: &UpdateArgs) {
+        self.tick_cooldown(args);
+        if let State::RoomTransition(time_left) = self.state {
+            let time_left = time_left - args.dt;
+            if time_left <= 0.0 {
+                self.start_level();
+                self.state = State::Playing;
+            } else {
+                self.state = State::RoomTransition(time_left);
+            }
+        }
+    }
+
+    pub(super) fn transition_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        let banner = format!("LEVEL {}", self.level);
+        self.draw_banner(banner.as_str(), 0.0, renderer);
+        self.draw_banner(self.room.intermission.as_str(), 45.0, renderer);
+    }
+}
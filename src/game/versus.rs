@@ -0,0 +1,133 @@
+use super::{Direction, Game, State};
+
+/// how a versus match is decided
+/// kills: the first player to shoot the other this many times wins, 0 for no limit
+/// time: seconds in a round, the player with the most kills when it runs out wins, 0 for no limit
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct VersusRules {
+    pub kills: u32,
+    pub time: f64,
+}
+
+/// the end of a versus match
+/// Winner: the number of the player who won, starting from 0
+/// Draw: the match ended with the kills level, by time running out or both reaching the limit at once
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Outcome {
+    Winner(usize),
+    Draw,
+}
+
+impl VersusRules {
+    /// how the match ended, none while it is still going
+    /// when the kill limit is reached the most kills win, so a limit reached by both in one update is a draw
+    pub fn outcome(&self, kills: &[u32], time: f64) -> Option<Outcome> {
+        if self.kills > 0 && kills.iter().any(|&k| k >= self.kills) {
+            return Some(leader(kills))
+        }
+        if self.time > 0.0 && time >= self.time {
+            return Some(leader(kills))
+        }
+        None
+    }
+}
+
+/// the player with the most kills, a draw when the top is shared
+pub fn leader(kills: &[u32]) -> Outcome {
+    let best = kills.iter().cloned().max().unwrap_or(0);
+    let mut top = kills.iter().enumerate().filter(|&(_, &k)| k == best);
+    match (top.next(), top.next()) {
+        (Some((winner, _)), None) => Outcome::Winner(winner),
+        _ => Outcome::Draw,
+    }
+}
+
+impl Game {
+    /// the rules the match is played with, from the gameplay settings
+    pub(super) fn versus_rules(&self) -> VersusRules {
+        VersusRules {
+            kills: self.settings.gameplay.versus_kills,
+            time: self.settings.gameplay.versus_time,
+        }
+    }
+
+    /// the way into the room for each player, the second player comes in from the right
+    pub(super) fn versus_entry(&self, number: usize) -> (f64, f64, Direction) {
+        if number.is_multiple_of(2) {
+            (75.0, self.dimensions[1] / 2.0, Direction::EAST)
+        } else {
+            (self.dimensions[0] - 75.0, self.dimensions[1] / 2.0, Direction::WEST)
+        }
+    }
+
+    /// brings a player back at their entry after they were shot or ran into something
+    pub(super) fn respawn(&mut self, number: usize) {
+        let (x, y, dir) = self.versus_entry(number);
+        let player = &mut self.players[number];
+        player.reset(x, y);
+        player.dir = dir;
+        player.is_moving = false;
+    }
+
+    /// the rest of a versus update after everything has moved and been hit
+    /// fallen players come back, the robots are topped up once every one is destroyed
+    /// and the match ends when the rules say so
+    pub(super) fn versus_update(&mut self) {
+        for number in 0..self.players.len() {
+            if !self.players[number].is_alive() {
+                self.respawn(number);
+            }
+        }
+        if self.enemies.is_empty() {
+            self.spawn_robots();
+        }
+        let kills: Vec<u32> = self.players.iter().map(|player| player.kills).collect();
+        if let Some(outcome) = self.versus_rules().outcome(&kills, self.play_time) {
+            self.end_versus(outcome);
+        }
+    }
+
+    /// ends the match with the outcome the banner will show
+    pub(super) fn end_versus(&mut self, outcome: Outcome) {
+        self.versus_outcome = Some(outcome);
+        self.state = State::Won;
+    }
+
+    /// the banner shown when the match is over
+    /// a joining game is not told the outcome and works it out from the host's kills the same way
+    pub(super) fn versus_banner(&self) -> String {
+        let kills: Vec<u32> = self.players.iter().map(|player| player.kills).collect();
+        match self.versus_outcome.unwrap_or_else(|| leader(&kills)) {
+            Outcome::Winner(winner) => format!("PLAYER {} WINS", winner + 1),
+            Outcome::Draw => "DRAW".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_first_to_kills() {
+        let rules = VersusRules { kills: 5, time: 0.0 };
+        assert!(rules.outcome(&[4, 4], 1000.0).is_none());
+        assert!(rules.outcome(&[4, 5], 10.0) == Some(Outcome::Winner(1)));
+        assert!(rules.outcome(&[6, 5], 10.0) == Some(Outcome::Winner(0)));
+        assert!(rules.outcome(&[5, 5], 10.0) == Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn test_timed_round() {
+        let rules = VersusRules { kills: 0, time: 60.0 };
+        assert!(rules.outcome(&[9, 2], 59.9).is_none());
+        assert!(rules.outcome(&[9, 2], 60.0) == Some(Outcome::Winner(0)));
+        assert!(rules.outcome(&[3, 3], 60.0) == Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn test_leader() {
+        assert!(leader(&[0, 0]) == Outcome::Draw);
+        assert!(leader(&[1, 2]) == Outcome::Winner(1));
+    }
+}
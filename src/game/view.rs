@@ -0,0 +1,68 @@
+/// how the fixed logical play area is fitted into the window
+/// scale: window pixels per logical unit, the same on both axes so nothing is stretched
+/// offset: where the play area starts in the window, leaving black bars at the sides
+/// or at the top and bottom when the window is a different shape than the play area
+/// window: the size of the window
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct View {
+    pub scale: f64,
+    pub offset: [f64; 2],
+    pub window: [f64; 2],
+}
+
+impl View {
+    /// the biggest scale that fits the logical area in the window, centred
+    pub fn fit(window: [f64; 2], logical: [f64; 2]) -> Self {
+        let scale = (window[0] / logical[0]).min(window[1] / logical[1]);
+        View {
+            scale,
+            offset: [(window[0] - logical[0] * scale) / 2.0, (window[1] - logical[1] * scale) / 2.0],
+            window,
+        }
+    }
+
+    /// the bars outside the play area as [x0, y0, x1, y1] in window coordinates
+    /// they are drawn over anything that pokes out of the play area
+    pub fn bars(&self) -> Vec<[f64; 4]> {
+        let mut bars = Vec::new();
+        let (w, h) = (self.window[0], self.window[1]);
+        let (x, y) = (self.offset[0], self.offset[1]);
+        if x > 0.0 {
+            bars.push([0.0, 0.0, x, h]);
+            bars.push([w - x, 0.0, w, h]);
+        }
+        if y > 0.0 {
+            bars.push([0.0, 0.0, w, y]);
+            bars.push([0.0, h - y, w, h]);
+        }
+        bars
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_fit_same_size() {
+        let view = View::fit([900.0, 600.0], [900.0, 600.0]);
+        assert!(view.scale == 1.0);
+        assert!(view.offset == [0.0, 0.0]);
+        assert!(view.bars().is_empty());
+    }
+
+    #[test]
+    fn test_fit_wide_window() {
+        let view = View::fit([1600.0, 600.0], [900.0, 600.0]);
+        assert!(view.scale == 1.0);
+        assert!(view.offset == [350.0, 0.0]);
+        assert!(view.bars() == vec![[0.0, 0.0, 350.0, 600.0], [1250.0, 0.0, 1600.0, 600.0]]);
+    }
+
+    #[test]
+    fn test_fit_tall_window() {
+        let view = View::fit([450.0, 600.0], [900.0, 600.0]);
+        assert!(view.scale == 0.5);
+        assert!(view.offset == [0.0, 150.0]);
+    }
+}
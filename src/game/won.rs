@@ -0,0 +1,34 @@
+use piston::input::*;
+
+use controls::Action;
+use render::Renderer;
+
+use super::{Game, Mode};
+
+impl Game {
+    pub(super) fn won_input(&mut self, button: &Button, is_press: bool) {
+        if !is_press {
+            return
+        }
+        if let Button::Keyboard(key) = *button {
+            if self.action_for(key).map(|(_, action)| action) == Some(Action::Restart) {
+                self.hard_reset();
+            }
+        }
+    }
+
+    pub(super) fn won_update(&mut self, args: &UpdateArgs) {
+        self.tick_cooldown(args);
+    }
+
+    pub(super) fn won_draw<R: Renderer>(&mut self, renderer: &mut R) {
+        self.draw_room(renderer);
+        if self.mode == Mode::Versus {
+            let banner = self.versus_banner();
+            self.draw_banner(&banner, 0.0, renderer);
+        } else {
+            self.draw_banner("CONGRATS YOU WON", 0.0, renderer);
+            self.draw_high_scores(60.0, 80.0, renderer);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use config;
+
+/// the file in the config directory that holds the high scores
+pub const HIGH_SCORES_FILE: &str = "highscores.txt";
+/// the first line of the file, the number is bumped if the format changes
+const HEADER: &str = "berzerk high scores";
+const VERSION: u32 = 1;
+/// how many scores the table keeps
+pub const MAX_HIGH_SCORES: usize = 10;
+/// letters in a set of initials
+pub const INITIALS_LEN: usize = 3;
+
+/// one line of the high score table
+#[derive(Clone, PartialEq, Debug)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: u32,
+    pub level: u32,
+}
+
+/// the best scores from highest to lowest
+/// the file starts with `berzerk high scores 1` followed by one `AAA score level` per line
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// reads the table from a file, starting empty if it is missing
+    /// a file that cannot be read is moved aside to `.corrupt` so it is not overwritten,
+    /// and the error says why so the caller can report it before starting with an empty table
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return Ok(HighScores::default()),
+        };
+        match HighScores::parse(&contents) {
+            Ok(scores) => Ok(scores),
+            Err(e) => {
+                let mut corrupt_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+                corrupt_name.push(".corrupt");
+                let _ = fs::rename(path, path.with_file_name(corrupt_name));
+                Err(e)
+            }
+        }
+    }
+
+    /// writes the table without ever leaving a half written file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        config::write_atomic(path, &self.to_file_string())
+    }
+
+    /// reads the file format, lines that do not parse are skipped
+    /// fails if the header is missing or from a newer version
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut lines = contents.lines();
+        let header = lines.next().unwrap_or("").trim();
+        if !header.starts_with(HEADER) {
+            return Err("missing header".to_string());
+        }
+        let version: u32 = header[HEADER.len()..].trim().parse()
+            .map_err(|_| format!("bad version in header `{}`", header))?;
+        if version > VERSION {
+            return Err(format!("version {} is newer than {}", version, VERSION));
+        }
+        let mut scores = HighScores::default();
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 3 || parts[0].chars().count() != INITIALS_LEN {
+                continue
+            }
+            if let (Ok(score), Ok(level)) = (parts[1].parse(), parts[2].parse()) {
+                scores.insert(parts[0], score, level);
+            }
+        }
+        Ok(scores)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut contents = format!("{} {}\n", HEADER, VERSION);
+        for entry in &self.entries {
+            contents.push_str(&format!("{} {} {}\n", entry.initials, entry.score, entry.level));
+        }
+        contents
+    }
+
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// whether a score is good enough to be added to the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0 && (self.entries.len() < MAX_HIGH_SCORES ||
+            self.entries.last().map(|lowest| score > lowest.score).unwrap_or(true))
+    }
+
+    /// adds a score below any equal ones and drops whatever falls off the end
+    /// returns where it was placed, or None if it did not make the table
+    pub fn insert(&mut self, initials: &str, score: u32, level: u32) -> Option<usize> {
+        let position = self.entries.iter().position(|entry| score > entry.score).unwrap_or(self.entries.len());
+        if position >= MAX_HIGH_SCORES {
+            return None
+        }
+        self.entries.insert(position, HighScore {
+            initials: initials.to_string(),
+            score,
+            level,
+        });
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(position)
+    }
+}
+
+/// three letters picked one at a time like an arcade cabinet
+/// letters: the letters picked so far, starting as AAA
+/// cursor: which letter is being changed
+#[derive(Clone, PartialEq, Debug)]
+pub struct Initials {
+    letters: [u8; INITIALS_LEN],
+    cursor: usize,
+}
+
+impl Default for Initials {
+    fn default() -> Self {
+        Initials {
+            letters: [b'A'; INITIALS_LEN],
+            cursor: 0,
+        }
+    }
+}
+
+impl Initials {
+    /// moves the current letter forward or back through the alphabet, wrapping around
+    pub fn cycle(&mut self, forward: bool) {
+        let letter = self.letters[self.cursor] - b'A';
+        let letter = if forward { (letter + 1) % 26 } else { (letter + 25) % 26 };
+        self.letters[self.cursor] = b'A' + letter;
+    }
+
+    /// moves to the previous or next letter
+    pub fn move_cursor(&mut self, forward: bool) {
+        if forward {
+            self.cursor = (self.cursor + 1).min(INITIALS_LEN - 1);
+        } else if self.cursor > 0 {
+            self.cursor -= 1;
+        }
+    }
+
+    /// sets the current letter and moves to the next one
+    pub fn type_letter(&mut self, letter: char) {
+        if letter.is_ascii_alphabetic() {
+            self.letters[self.cursor] = letter.to_ascii_uppercase() as u8;
+            self.move_cursor(true);
+        }
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn text(&self) -> String {
+        self.letters.iter().map(|letter| *letter as char).collect()
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_insert_in_order() {
+        let mut scores = HighScores::default();
+        scores.insert("AAA", 100, 1);
+        scores.insert("BBB", 300, 2);
+        assert!(scores.insert("CCC", 100, 1) == Some(2));
+        assert!(scores.entries()[0].initials == "BBB");
+        assert!(scores.entries()[1].initials == "AAA");
+    }
+
+    #[test]
+    fn test_table_is_capped() {
+        let mut scores = HighScores::default();
+        for i in 0..MAX_HIGH_SCORES as u32 {
+            scores.insert("AAA", 100 + i, 1);
+        }
+        assert!(!scores.qualifies(100));
+        assert!(scores.qualifies(101));
+        assert!(scores.insert("ZZZ", 50, 1).is_none());
+        assert!(scores.entries().len() == MAX_HIGH_SCORES);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut scores = HighScores::default();
+        scores.insert("ABC", 1200, 4);
+        scores.insert("XYZ", 50, 1);
+        assert!(HighScores::parse(&scores.to_file_string()) == Ok(scores));
+    }
+
+    #[test]
+    fn test_corrupt_files() {
+        assert!(HighScores::parse("").is_err());
+        assert!(HighScores::parse("garbage\nAAA 100 1\n").is_err());
+        assert!(HighScores::parse("berzerk high scores 99\n").is_err());
+        let scores = HighScores::parse("berzerk high scores 1\nAAA 100 1\nnot a score\nBB 5 1\n").unwrap();
+        assert!(scores.entries().len() == 1);
+    }
+
+    #[test]
+    fn test_load_moves_corrupt_file_aside() {
+        let path = ::std::env::temp_dir().join("berzerk-test-highscores.txt");
+        let corrupt = ::std::env::temp_dir().join("berzerk-test-highscores.txt.corrupt");
+        fs::write(&path, "garbage\n").unwrap();
+        assert!(HighScores::load(&path).is_err());
+        assert!(!path.exists() && corrupt.exists());
+        assert!(HighScores::load(&path) == Ok(HighScores::default()));
+        let _ = fs::remove_file(&corrupt);
+    }
+
+    #[test]
+    fn test_initials() {
+        let mut initials = Initials::default();
+        initials.cycle(false);
+        assert!(initials.text() == "ZAA");
+        initials.type_letter('b');
+        initials.type_letter('c');
+        initials.type_letter('d');
+        assert!(initials.text() == "BCD");
+        assert!(initials.cursor() == 2);
+    }
+}
@@ -0,0 +1,30 @@
+//! berzerk as a library: the simulation and its models, campaign loading, settings, audio
+//! and the headless interfaces for bots, with the windowed game and the simulator as binaries on top
+//!
+//! `game::Game` is the game itself, `environment::Environment` plays it without a window one step at a time
+//! and `simulation` plays whole batches of games for statistics
+
+extern crate piston;
+extern crate opengl_graphics;
+extern crate glutin_window;
+#[macro_use]
+extern crate serde_json;
+
+pub mod bot;
+pub mod campaign;
+pub mod config;
+pub mod controls;
+pub mod difficulty;
+pub mod environment;
+pub mod game;
+pub mod highscores;
+pub mod models;
+pub mod music;
+pub mod net;
+pub mod protocol;
+pub mod render;
+pub mod replay;
+pub mod settings;
+pub mod simulation;
+pub mod sprites;
+pub mod terminal;
@@ -1,48 +1,151 @@
+extern crate berzerk;
 extern crate piston;
 extern crate opengl_graphics;
 extern crate glutin_window;
 
-use opengl_graphics::glyph_cache::GlyphCache;
+use opengl_graphics::GlyphCache;
 use piston::window::WindowSettings;
 use glutin_window::GlutinWindow as Window;
-use opengl_graphics::{GlGraphics, OpenGL};
+use opengl_graphics::{GlGraphics, OpenGL, TextureSettings};
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process;
 
-pub mod game;
-pub mod models;
-pub mod music;
+use berzerk::{campaign, config, game, highscores, music, net, protocol, replay, settings, sprites, terminal};
+use berzerk::render::SpriteImages;
 
-pub const W_HEIGHT: f64 = 600.0;
-pub const W_WIDTH: f64 = 900.0;
-
-/// constructs a window and starts game instance 
-fn main() {
-    let opengl = OpenGL::V3_2;
-    let mut window: Window = WindowSettings::new("berzerk",
-                                                 [W_WIDTH as u32, W_HEIGHT as u32])
+/// constructs a window from the video settings
+fn build_window(video: &settings::VideoSettings) -> Window {
+    WindowSettings::new("berzerk", [video.width, video.height])
+        .fullscreen(video.fullscreen)
         .exit_on_esc(true)
         .build()
-        .expect("Error creating window");
+        .expect("Error creating window")
+}
+
+/// remembers the settings changed while playing, like the window size, for next time
+fn save_settings(g: &game::Game) {
+    if let Err(e) = g.save_settings() {
+        eprintln!("could not save settings: {}", e);
+    }
+}
 
-    let mut gl = GlGraphics::new(opengl);
-    let mut g = game::Game::new(W_WIDTH, W_HEIGHT);
-    let mut glyph_cache = GlyphCache::new("assets/Amatic-Bold.ttf").expect("Error getting fonts");
-    g.run(&mut window, &mut gl, &mut glyph_cache);
+/// `--render-replay REPLAY GIF` plays a replay saved by berzerk-sim again and writes it as a GIF, with no window
+fn render_replay(args: &[String]) -> Result<(), String> {
+    let (replay_path, gif_path) = match (args.get(1), args.get(2)) {
+        (Some(replay_path), Some(gif_path)) => (Path::new(replay_path), Path::new(gif_path)),
+        _ => return Err("--render-replay needs a replay file and a GIF file".to_string()),
+    };
+    let replay = replay::Replay::load(replay_path)?;
+    let campaign = replay.load_campaign()?;
+    let sprites = SpriteImages::load()?;
+    let file = File::create(gif_path).map_err(|e| format!("{}: {}", gif_path.display(), e))?;
+    let mut out = BufWriter::new(file);
+    replay.render_gif(campaign, &sprites, &mut out).and_then(|_| out.flush())
+        .map_err(|e| format!("{}: {}", gif_path.display(), e))
 }
 
+/// sets up a network game from the command line
+/// `--host [PORT]` waits for a second player to join before opening the window
+/// `--join ADDRESS` plays as the second player in a hosted game, e.g. `--join 127.0.0.1:7777`
+fn connect(g: &mut game::Game, args: &[String]) {
+    match args.first().map(|arg| arg.as_str()) {
+        Some("--host") => {
+            let port = args.get(1).and_then(|port| port.parse().ok()).unwrap_or(net::DEFAULT_PORT);
+            eprintln!("waiting for a player to join on port {}", port);
+            let connection = TcpListener::bind(("0.0.0.0", port))
+                .and_then(|listener| net::Connection::accept(&listener))
+                .unwrap_or_else(|e| {
+                    eprintln!("could not host a game: {}", e);
+                    process::exit(1)
+                });
+            g.host(connection);
+        },
+        Some("--join") => {
+            let address = args.get(1).cloned().unwrap_or(format!("127.0.0.1:{}", net::DEFAULT_PORT));
+            let connection = net::Connection::join(address.as_str()).unwrap_or_else(|e| {
+                eprintln!("could not join {}: {}", address, e);
+                process::exit(1)
+            });
+            g.join(connection);
+        },
+        Some(arg) => eprintln!("unknown argument {}, use --host [PORT], --join ADDRESS, --agent, --terminal or --render-replay REPLAY GIF", arg),
+        None => (),
+    }
+}
 
-#[cfg(test)] 
-mod berzerk_test {
-    use super::*;
+/// loads the settings, constructs a window and starts game instance 
+/// the window, and everything tied to its OpenGL context, is made again when
+/// the game asks for it after a fullscreen or window size change
+/// `--agent` plays without a window for a program talking to stdin and stdout instead
+/// `--terminal` plays without a window inside the terminal, with the sound off
+/// `--render-replay REPLAY GIF` writes a replay to a GIF without a window
+fn main() {
+    let settings = settings::Settings::load(&config::config_path(settings::SETTINGS_FILE));
 
-    #[test]
-    fn test_window_creation() {
-        let opengl = OpenGL::V3_2;
-        let _window: Window = WindowSettings::new("create test",
-                                             [500, 500])
-        .exit_on_esc(true)
-        .build()
-        .expect("Error creating window"); 
-        let _gl = GlGraphics::new(opengl);    
+    let high_scores = highscores::HighScores::load(&config::config_path(highscores::HIGH_SCORES_FILE))
+        .unwrap_or_else(|e| {
+            eprintln!("could not read high scores, starting a new table: {}", e);
+            highscores::HighScores::default()
+        });
+
+    let campaign = if settings.gameplay.campaign.is_empty() {
+        campaign::Campaign::default()
+    } else {
+        campaign::Campaign::load(Path::new(&settings.gameplay.campaign)).unwrap_or_else(|e| {
+            eprintln!("could not load campaign, playing the classic one: {}", e);
+            campaign::Campaign::default()
+        })
+    };
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(|arg| arg.as_str()) == Some("--agent") {
+        // errors have already been sent to the agent
+        let stdout = io::stdout();
+        if protocol::run(settings, campaign, BufReader::new(io::stdin()), &mut stdout.lock()).is_err() {
+            process::exit(1);
+        }
+        return
+    }
+
+    if args.first().map(|arg| arg.as_str()) == Some("--render-replay") {
+        if let Err(e) = render_replay(&args) {
+            eprintln!("could not render the replay: {}", e);
+            process::exit(1);
+        }
+        return
+    }
+
+    if args.first().map(|arg| arg.as_str()) == Some("--terminal") {
+        let mut g = game::Game::new(settings, high_scores, campaign);
+        g.set_silent(true);
+        if let Err(e) = terminal::run(&mut g) {
+            eprintln!("could not play in the terminal: {}", e);
+            process::exit(1);
+        }
+        save_settings(&g);
+        return
     }
 
-}
\ No newline at end of file
+    if let Err(e) = music::start() {
+        eprintln!("could not open the audio device, playing without sound: {}", e);
+    }
+
+    let opengl = OpenGL::V3_2;
+    let mut g = game::Game::new(settings, high_scores, campaign);
+    connect(&mut g, &args);
+    loop {
+        let mut window = build_window(&g.settings().video);
+        let mut gl = GlGraphics::new(opengl);
+        let mut glyph_cache = GlyphCache::new("assets/Amatic-Bold.ttf", (), TextureSettings::new()).expect("Error getting fonts");
+        let sprites = sprites::Sprites::load().expect("Error loading sprites");
+        if g.run(&mut window, &mut gl, &mut glyph_cache, &sprites) == game::RunEnd::Closed {
+            break;
+        }
+    }
+    save_settings(&g);
+}
+
@@ -3,12 +3,12 @@ extern crate graphics;
 extern crate glutin_window;
 extern crate opengl_graphics;
 
-use opengl_graphics::GlGraphics;
 use std::f64;
 use models::vector::Vector;
 use models::enemy::Enemy;
 use game::Direction;
 use models::player::Player;
+use render::Renderer;
 
 pub const BULLET_SPEED:f64 = 5.0;
 pub const BULLET_SIZE:f64 = 5.0;
@@ -18,38 +18,56 @@ pub const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
 /// pos: the position of the bullet in the window
 /// alive: used to remove bullets when they collide with game objects
 /// dir: the direction the bullet is heading
+/// speed: how far the bullet moves each update
 pub struct Bullet {
     pos: Vector,
     pub alive: bool,
     dir: Direction,    
+    speed: f64,
 }
 
 impl Bullet {
-    /// creates a new bullet
+    /// creates a new bullet moving at BULLET_SPEED
     pub fn new(xpos: f64, ypos: f64, dir: Direction) -> Self {
+        Bullet::with_speed(xpos, ypos, dir, BULLET_SPEED)
+    }
+
+    /// creates a new bullet moving at the given speed
+    pub fn with_speed(xpos: f64, ypos: f64, dir: Direction, speed: f64) -> Self {
         Bullet {
             pos: Vector::new(xpos, ypos),
             alive: true,
             dir,
+            speed,
         }
     }
 
+    /// where the bullet is, used to send it over the network
+    pub fn pos(&self) -> (f64, f64) {
+        (self.pos.x, self.pos.y)
+    }
+
+    pub fn dir(&self) -> Direction {
+        self.dir
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
     /// draws the bullet as a rectangle on the screen
     /// the function uses the global BULLET_SIZE
-    pub fn draw(&self, c: graphics::Context, gl: &mut GlGraphics) {
-        use self::graphics::*;
-        let square = rectangle::square(0.0, 0.0, BULLET_SIZE);
-        let transform = c.transform.trans(self.pos.x-20.0, self.pos.y);
-        rectangle(WHITE, square, transform, gl);
+    pub fn draw<R: Renderer>(&self, renderer: &mut R) {
+        renderer.rectangle(WHITE, [self.pos.x-20.0, self.pos.y, BULLET_SIZE, BULLET_SIZE]);
     }
 
     /// adjusts the direction of the bullet
     pub fn update(&mut self) {
         match self.dir {
-            Direction::EAST => self.pos.x += BULLET_SPEED,
-            Direction::NORTH => self.pos.y -= BULLET_SPEED,
-            Direction::WEST => self.pos.x -= BULLET_SPEED,
-            Direction::SOUTH => self.pos.y += BULLET_SPEED,
+            Direction::EAST => self.pos.x += self.speed,
+            Direction::NORTH => self.pos.y -= self.speed,
+            Direction::WEST => self.pos.x -= self.speed,
+            Direction::SOUTH => self.pos.y += self.speed,
         }
     }  
 
@@ -86,7 +104,7 @@ impl Bullet {
         wall[0]+(wall[2]-wall[0]) >= self.pos.x;
         let collision_y: bool = self.pos.y + BULLET_SIZE >= wall[1] &&
             wall[1] + (wall[3]-wall[1]) >= self.pos.y;
-        return collision_x && collision_y        
+        collision_x && collision_y        
     }  
 
 }
@@ -123,6 +141,13 @@ mod berzerk_test {
         let expected = 100.0 + BULLET_SPEED;
         assert!(t_bullet.pos.y == expected);
     }   
+
+    #[test]
+    fn test_update_with_speed() {
+        let mut t_bullet = Bullet::with_speed(0.0,0.0, Direction::EAST, 8.0);
+        t_bullet.update();
+        assert!(t_bullet.pos.x == 8.0);
+    }
 }
 
 
@@ -3,72 +3,112 @@ extern crate graphics;
 extern crate glutin_window;
 extern crate opengl_graphics;
 extern crate rand;
-extern crate find_folder;
 
-use opengl_graphics::{GlGraphics, Texture};
 use std::f64;
 use models::vector::Vector;
+use render::{Renderer, Sprite};
 use self::rand::Rng;
 use game::Direction; //where is player in relation to enemy shoot in that direction
 
 pub const ENEMY_SIZE: f64 = 40.0;
-const ENEMY_SPEED: f64 = 5.0;
+pub const ENEMY_SPEED: f64 = 5.0;
 const ENEMY_PROB_MOVEMENT: u32= 30;
 
+/// the kinds of robot, they look the same but are tinted differently
+/// Drone: wanders toward the player slowly and never shoots
+/// Soldier: the original robot
+/// Hunter: moves toward the player much more often
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EnemyKind {
+    Drone,
+    Soldier,
+    Hunter,
+}
+
+impl EnemyKind {
+    /// the range the robot rolls in each update, it moves when it rolls a 3
+    fn movement_range(&self) -> u32 {
+        match *self {
+            EnemyKind::Drone => ENEMY_PROB_MOVEMENT * 2,
+            EnemyKind::Soldier => ENEMY_PROB_MOVEMENT,
+            EnemyKind::Hunter => ENEMY_PROB_MOVEMENT / 3,
+        }
+    }
+
+    /// drones never shoot
+    pub fn can_shoot(&self) -> bool {
+        *self != EnemyKind::Drone
+    }
+
+    /// the colour the robot image is multiplied by
+    pub fn tint(&self) -> [f32; 4] {
+        match *self {
+            EnemyKind::Drone => [0.5, 1.0, 0.5, 1.0],
+            EnemyKind::Soldier => [1.0, 1.0, 1.0, 1.0],
+            EnemyKind::Hunter => [1.0, 0.45, 0.45, 1.0],
+        }
+    }
+}
 
 /// struct contains mutable settings for enemies
 /// pos: position in window
 /// alive: whether the enemy should be removed or not
-/// texture: contains the image for enemy
 /// size: size of enemy
 /// dir: direction the enemy is moving towards
+/// kind: how the enemy behaves
+/// speed: how far the enemy moves in one step
 pub struct Enemy {
     pub pos: Vector,
     pub alive: bool,    
-    texture: Result<Texture, String>,
     pub size: f64,
     pub dir: Direction,
+    pub kind: EnemyKind,
+    pub speed: f64,
 }
 
 
 impl Enemy {
-    /// creates a new enemy 
+    /// creates a new soldier 
     pub fn new(x: f64, y: f64 ) -> Self {
+        Enemy::with_kind(x, y, EnemyKind::Soldier, ENEMY_SPEED)
+    }        
+
+    /// creates a new enemy of the given kind and speed
+    pub fn with_kind(x: f64, y: f64, kind: EnemyKind, speed: f64) -> Self {
         Enemy {
             pos: Vector::new(x, y),
             alive: true,
-            texture: Texture::from_path(find_folder::Search::ParentsThenKids(3, 3)
-                .for_folder("assets")
-                .unwrap()
-                .join("enemy.png")),
             size: ENEMY_SIZE,
             dir: Direction::EAST,
+            kind,
+            speed,
         }
-    }        
+    }
 
     ///draws the enemy on the screen
-    pub fn draw(&mut self, c: self::graphics::Context, gl: &mut GlGraphics) {
-        use self::graphics::*;
-
-        let transform = c.transform
-            .trans(self.pos.x, self.pos.y)
-            .trans(-ENEMY_SIZE / 2.0, -ENEMY_SIZE / 2.0);
-
-        match self.texture {
-            Ok(ref t) => image(t, transform, gl),
-            _ => {}
-        }
+    pub fn draw<R: Renderer>(&self, renderer: &mut R) {
+        renderer.sprite(Sprite::Enemy, self.kind.tint(), self.pos.x - ENEMY_SIZE / 2.0, self.pos.y - ENEMY_SIZE / 2.0);
     }
 
-    /// randomly picks whether the enemy should move toward the player
-    pub fn update(&mut self, playerx:f64,playery:f64) {
-        let num: u32 = rand::thread_rng().gen_range(1, ENEMY_PROB_MOVEMENT);
+    /// randomly picks whether the enemy should move toward the nearest of the players
+    pub fn update<R: Rng>(&mut self, players: &[&Vector], rng: &mut R) {
+        let num: u32 = rng.gen_range(1, self.kind.movement_range());
 
         if num == 3 {
-            self.move_toward_player(playerx,playery);            
+            if let Some(target) = self.nearest(players) {
+                self.move_toward_player(target.x, target.y);            
+            }
         }
     }
 
+    /// the closest position, none when there are no players left to chase
+    fn nearest<'a>(&self, players: &[&'a Vector]) -> Option<&'a Vector> {
+        let distance = |pos: &Vector| (pos.x - self.pos.x).powi(2) + (pos.y - self.pos.y).powi(2);
+        players.iter()
+            .min_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap_or(::std::cmp::Ordering::Equal))
+            .cloned()
+    }
+
     fn set_direction(&mut self, dx: f64, dy:f64){
         if dx*dx > dy*dy {
             if dx >  0.0 {
@@ -90,14 +130,14 @@ impl Enemy {
         let mut dx = self.pos.x - playerx;
         let mut dy = self.pos.y - playery;
         let dist =  (dx*dx + dy*dy).sqrt();
-        dx = dx/dist;
-        dy = dy/dist;
+        dx /= dist;
+        dy /= dist;
 
         self.set_direction(dx,dy);
 
         // if dy > 0.0 {println!("going up");}
-        self.pos.x -= dx * ENEMY_SPEED;
-        self.pos.y -= dy * ENEMY_SPEED;
+        self.pos.x -= dx * self.speed;
+        self.pos.y -= dy * self.speed;
     } 
 
     ///checks for enemy collision with wall
@@ -108,7 +148,7 @@ impl Enemy {
         let collision_y: bool = self.pos.y + ENEMY_SIZE >= wall[1] &&
             wall[1] + (wall[3]-wall[1]) >= self.pos.y;
             
-        return collision_x && collision_y
+        collision_x && collision_y
     }        
 }
 
@@ -117,39 +157,20 @@ impl Enemy {
 
 #[cfg(test)]
 mod berzerk_test {
-    use piston::window::WindowSettings;
-    use glutin_window::GlutinWindow as Window;
-    use opengl_graphics::OpenGL;
     use super::*;
 
     //TODO: Find a way to make a test enemy that I could use in all the functions
 
     #[test]
     fn test_new_enemy() {
-        // set up that is needed to make the enemy
-        // required because I am using texture which depends on this set up
-        let opengl = OpenGL::V3_2;
-        //OpenGL function pointers must be loaded before creating the `Gl` backend
-        //That is why this window is created
-        let _window: Window = WindowSettings::new("create test", [500, 500])
-            .exit_on_esc(true)
-            .build()
-            .expect("Error creating window"); 
-        let _gl = GlGraphics::new(opengl);
-        let _t_enemy = Enemy::new(0.0,100.0);
+        let t_enemy = Enemy::new(0.0,100.0);
+        assert!(t_enemy.alive);
     }
 
     #[test]
     // I am testing this private function instead of the public update function
     // because this is where the logic is and update has a random chance of running this logic
     fn test_move_toward_player() {
-        let opengl = OpenGL::V3_2;
-        let _window: Window = WindowSettings::new("create test", [500, 500])
-            .exit_on_esc(true)
-            .build()
-            .expect("Error creating window"); 
-        let _gl = GlGraphics::new(opengl);
-
         let t_pos_before = 50.0;
         let mut t_enemy = Enemy::new(t_pos_before,t_pos_before);
         t_enemy.move_toward_player(10.0,10.0);
@@ -157,5 +178,13 @@ mod berzerk_test {
         assert!(t_enemy.pos.y < t_pos_before);
     }
 
-}
+    #[test]
+    fn test_nearest_player() {
+        let t_enemy = Enemy::new(100.0, 100.0);
+        let far = Vector::new(400.0, 100.0);
+        let near = Vector::new(100.0, 150.0);
+        assert!(t_enemy.nearest(&[&far, &near]).map(|pos| pos.x) == Some(100.0));
+        assert!(t_enemy.nearest(&[]).is_none());
+    }
 
+}
@@ -3,107 +3,140 @@ extern crate graphics;
 extern crate glutin_window;
 extern crate opengl_graphics;
 extern crate rand;
-extern crate find_folder;
 
-use opengl_graphics::GlGraphics;
-use opengl_graphics::Texture;
 use std::f64;
 use models::vector::Vector;
 use game::Direction;
 use models::enemy::Enemy;
+use models::bullet::Bullet;
+use render::{Renderer, Sprite};
 use self::rand::Rng;
 
 pub const PLAYER_X_SIZE: f64 = 20.0;
 pub const PLAYER_Y_SIZE: f64 = 33.0;
 const PLAYER_SPEED: f64 = 5.0;
+pub const STARTING_HEALTH: u32 = 3;
+/// the colour each player's sprite and hearts are multiplied by, the first player is drawn as is
+pub const PLAYER_COLORS: [[f32; 4]; 2] = [[1.0, 1.0, 1.0, 1.0], [0.55, 0.8, 1.0, 1.0]];
+
+/// what took one of a player's lives
+/// RobotBullet: shot by a robot
+/// Robot: ran into a robot
+/// Wall: touched an electrified wall
+/// PlayerBullet: shot by the other player
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Cause {
+    RobotBullet,
+    Robot,
+    Wall,
+    PlayerBullet,
+}
 
 /// contains mutable settings for the player
 /// pos: position in window
 /// dir: direction the player is heading
-/// health: starts with 3 health
-/// texture: image for the player
-/// heart_texture: is the image for health hearts
+/// health: lives left, filled back up to max_health when a room starts
+/// max_health: starts at 3 and goes up with every extra life
 /// is_moving: used when player is moving
 /// collided: if the player has collided
 /// player_x_size & y_size is the player height and width 
+/// score: points from robots this player shot
+/// kills: times this player shot the other player in a versus match
+/// color: tint for the sprite and hearts so players can be told apart
+/// bullets: this player's shots still in the air
+/// lives_lost: what took each life lost this game, in order
 pub struct Player {
     pub pos: Vector,
     pub dir: Direction,    
     pub health: u32,    
-    texture: Result<Texture, String>,
-    heart_texture: Result<Texture, String>,    
+    pub max_health: u32,
     pub is_moving: bool,
     pub collided: bool,
     pub player_x_size: f64,
-    pub player_y_size:f64
+    pub player_y_size:f64,
+    pub score: u32,
+    pub kills: u32,
+    pub color: [f32; 4],
+    pub bullets: Vec<Bullet>,
+    pub lives_lost: Vec<Cause>,
 }
 
 
 impl Player {
 
     /// creates a new player 
-    /// is hard coded to find image in assets for player and heart
     /// starts with 3 lives
     pub fn new(x: f64, y: f64 ) -> Self {
         Player {
             pos: Vector::new(x, y),
             dir: Direction::EAST,                                 
-            health: 3,
-            texture: Texture::from_path(find_folder::Search::ParentsThenKids(3, 3)
-                .for_folder("assets")
-                .unwrap()
-                .join("player.png")),
-            heart_texture:   Texture::from_path(find_folder::Search::ParentsThenKids(3, 3)
-                .for_folder("assets")
-                .unwrap()
-                .join("heart.png")),   
+            health: STARTING_HEALTH,
+            max_health: STARTING_HEALTH,
             is_moving: false,
             collided: false,
             player_x_size: PLAYER_X_SIZE,
             player_y_size: PLAYER_Y_SIZE,
+            score: 0,
+            kills: 0,
+            color: PLAYER_COLORS[0],
+            bullets: Vec::new(),
+            lives_lost: Vec::new(),
         }
     }    
 
+    /// creates the player with the given number, starting from 0, tinted with its colour
+    pub fn numbered(number: usize, x: f64, y: f64) -> Self {
+        let mut player = Player::new(x, y);
+        player.color = PLAYER_COLORS[number % PLAYER_COLORS.len()];
+        player
+    }
+
     /// resets the player position and health
     pub fn reset(&mut self, x: f64, y: f64) {
         self.pos.x = x;
         self.pos.y = y;
-        self.health = 3;
+        self.health = self.max_health;
     }   
 
-    /// draws the player moving in the direction it is going
-    pub fn draw(&mut self, c: self::graphics::Context, gl: &mut GlGraphics) {
-        use self::graphics::*;
+    /// a player without lives is out until the next room starts
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
 
-        let transform = c.transform
-            .trans(self.pos.x, self.pos.y)
-            .trans(-PLAYER_X_SIZE / 2.0, -PLAYER_Y_SIZE / 2.0);
+    /// takes a life away, two hits in the same update can not go below zero
+    /// and only the first is remembered
+    pub fn lose_life(&mut self, cause: Cause) {
+        if self.health > 0 {
+            self.health -= 1;
+            self.lives_lost.push(cause);
+        }
+    }
 
-        match self.texture {
-            Ok(ref t) => image(t, transform, gl),
-            _ => {}
+    /// an extra life that is kept for the rest of the game
+    /// a player who is out only gets it when the next room fills their lives back up
+    pub fn add_life(&mut self) {
+        self.max_health += 1;
+        if self.health > 0 {
+            self.health += 1;
         }
+    }
 
-        self.handle_moving();
+    /// draws the player, using the moving image while it is moving
+    pub fn draw<R: Renderer>(&self, renderer: &mut R) {
+        let sprite = if self.is_moving { Sprite::PlayerMove } else { Sprite::Player };
+        renderer.sprite(sprite, self.color, self.pos.x - PLAYER_X_SIZE / 2.0, self.pos.y - PLAYER_Y_SIZE / 2.0);
     }
 
-    fn handle_moving(&mut self) {
+    /// moves the player one step in the direction it is heading
+    /// called once per update so the player stays still when the game is not running
+    pub fn update(&mut self) {
         if self.is_moving {
-            self.texture = Texture::from_path(find_folder::Search::ParentsThenKids(3, 3)
-                .for_folder("assets")
-                .unwrap()
-                .join("player_move.png"));
             match self.dir {
                 Direction::WEST => self.pos.x -= PLAYER_SPEED,
                 Direction::NORTH => self.pos.y -=PLAYER_SPEED,
                 Direction::EAST => self.pos.x += PLAYER_SPEED,
                 Direction::SOUTH => self.pos.y += PLAYER_SPEED,
             }
-        } else {
-            self.texture =Texture::from_path(find_folder::Search::ParentsThenKids(3, 3)
-                .for_folder("assets")
-                .unwrap()
-                .join("player.png"));        
         }
     }
 
@@ -113,7 +146,7 @@ impl Player {
         wall[0]+(wall[2]-wall[0]) >= self.pos.x;
         let collision_y: bool = self.pos.y + PLAYER_Y_SIZE >= wall[1] &&
             wall[1] + (wall[3]-wall[1]) >= self.pos.y;
-        return collision_x && collision_y
+        collision_x && collision_y
     } 
 
     /// checks for collision with enemy
@@ -122,82 +155,91 @@ impl Player {
         enemy.pos.x+enemy.size >= self.pos.x;
         let collision_y: bool = self.pos.y + PLAYER_Y_SIZE >= enemy.pos.y &&
             enemy.pos.y + enemy.size >= self.pos.y;
-        return collision_x && collision_y
+        collision_x && collision_y
     }    
 
     /// places the enemies randomly in the window
     /// only choose from places where walls do not exist
-    pub fn place_random(&mut self, dimensions: [f64;2]){
-        let rand_block: u32 = rand::thread_rng().gen_range(1, 4);
+    pub fn place_random<R: Rng>(&mut self, dimensions: [f64;2], rng: &mut R){
+        let rand_block: u32 = rng.gen_range(1, 4);
         let mut randx: f64;
         let mut randy: f64;
 
         match rand_block {
             1 => { //left
-                randx= rand::thread_rng().gen_range(45.0, dimensions[0]/4.0-20.0);
-                randy= rand::thread_rng().gen_range(40.0, dimensions[1]-135.0);
+                randx= rng.gen_range(45.0, dimensions[0]/4.0-20.0);
+                randy= rng.gen_range(40.0, dimensions[1]-135.0);
             },
             2 => { //top
-                randx= rand::thread_rng().gen_range(dimensions[0]/4.0+50.0, (dimensions[0]/4.0)*3.0);
-                randy= rand::thread_rng().gen_range(40.0, dimensions[1]/4.0);
+                randx= rng.gen_range(dimensions[0]/4.0+50.0, (dimensions[0]/4.0)*3.0);
+                randy= rng.gen_range(40.0, dimensions[1]/4.0);
             },
             3 => { //right
-                randx= rand::thread_rng().gen_range((dimensions[0]/4.0)*3.0+50.0, dimensions[0]-50.0);
-                randy= rand::thread_rng().gen_range(40.0, dimensions[1]-135.0);                
+                randx= rng.gen_range((dimensions[0]/4.0)*3.0+50.0, dimensions[0]-50.0);
+                randy= rng.gen_range(40.0, dimensions[1]-135.0);                
             },
             4 => { //bottom
-                randx= rand::thread_rng().gen_range(dimensions[0]/4.0+50.0, (dimensions[0]/4.0)*3.0);
-                randy= rand::thread_rng().gen_range((dimensions[1]/4.0)*3.0, dimensions[1]-135.0);                
+                randx= rng.gen_range(dimensions[0]/4.0+50.0, (dimensions[0]/4.0)*3.0);
+                randy= rng.gen_range((dimensions[1]/4.0)*3.0, dimensions[1]-135.0);                
             },
             _ => {
-                randx= rand::thread_rng().gen_range(45.0, dimensions[0]/4.0-20.0);
-                randy = rand::thread_rng().gen_range(40.0, dimensions[1]-135.0);            
+                randx= rng.gen_range(45.0, dimensions[0]/4.0-20.0);
+                randy = rng.gen_range(40.0, dimensions[1]-135.0);            
             }
         }
 
         //if enemy too close to the player starting try again 
         while randx > 40.0 && randx < 90.0 && randy > dimensions[1] / 2.0 - 50.0 && randy < dimensions[1] / 2.0 + 50.0 {
-            randx = rand::thread_rng().gen_range(45.0, dimensions[0]/4.0-10.0); 
-            randy = rand::thread_rng().gen_range(40.0, dimensions[1]-135.0); 
+            randx = rng.gen_range(45.0, dimensions[0]/4.0-10.0); 
+            randy = rng.gen_range(40.0, dimensions[1]-135.0); 
         }  
         self.pos.x =  randx;
         self.pos.y = randy;
     } 
 
     /// draws the hearts on the bottom of the screen showing lives left
-    pub fn draw_lives(& self, posx: f64, posy: f64, c: self::graphics::Context, gl: &mut GlGraphics) {
-        use self::graphics::*;
-
-        let transform = c.transform
-            .trans(posx, posy)
-            .trans(-30.0 / 2.0, -30.0 / 2.0);
-
-        match self.heart_texture {
-            Ok(ref t) => image(t, transform, gl),
-            _ => { println!("something went wrong unwrapping heart texture.");}
-        }                
+    pub fn draw_lives<R: Renderer>(&self, posx: f64, posy: f64, renderer: &mut R) {
+        renderer.sprite(Sprite::Heart, self.color, posx - 30.0 / 2.0, posy - 30.0 / 2.0);
     }
 }
 
 #[cfg(test)] 
 mod berzerk_test {
-    use piston::window::WindowSettings;
-    use glutin_window::GlutinWindow as Window;
-    use opengl_graphics::OpenGL;
     use super::*;
 
     #[test]
     fn test_new_player() {
-        let opengl = OpenGL::V3_2;
-        //OpenGL function pointers must be loaded before creating the `Gl` backend
-        //That is why this window is created
-           let _window: Window = WindowSettings::new("create test",
-                                                     [500, 500])
-            .exit_on_esc(true)
-            .build()
-            .expect("Error creating window"); 
-        let _gl = GlGraphics::new(opengl);
-        let _t_player = Player::new(0.0,100.0);
+        let t_player = Player::new(0.0,100.0);
+        assert!(t_player.health == 3);
+    }
+
+    #[test]
+    fn test_extra_life_kept_on_reset() {
+        let mut t_player = Player::new(0.0,100.0);
+        t_player.add_life();
+        t_player.health = 1;
+        t_player.reset(0.0, 100.0);
+        assert!(t_player.health == STARTING_HEALTH + 1);
+    }
+
+    #[test]
+    fn test_extra_life_does_not_revive() {
+        let mut t_player = Player::new(0.0,100.0);
+        t_player.health = 0;
+        t_player.add_life();
+        assert!(t_player.health == 0);
+        assert!(t_player.max_health == STARTING_HEALTH + 1);
+    }
+
+    #[test]
+    fn test_lose_life_stops_at_zero() {
+        let mut t_player = Player::numbered(1, 0.0, 100.0);
+        t_player.health = 1;
+        t_player.lose_life(Cause::Wall);
+        t_player.lose_life(Cause::Robot);
+        assert!(t_player.health == 0 && !t_player.is_alive());
+        assert!(t_player.lives_lost == vec![Cause::Wall]);
+        assert!(t_player.color == PLAYER_COLORS[1]);
     }
 
     // #[test]
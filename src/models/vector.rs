@@ -6,7 +6,7 @@ pub struct Vector {
 
 impl Vector {
     pub fn new(x: f64, y: f64) -> Self {
-        Vector { x: x, y: y }
+        Vector { x, y }
     }
 
 }
@@ -2,80 +2,141 @@
 
 extern crate sdl2;
 
-use music::sdl2::audio::{AudioCallback, AudioSpecDesired,AudioSpecWAV,AudioCVT};
-use std::time::Duration;
-use std::borrow::Cow;
-use std::path::{PathBuf, Path};
-use std::env::*;
-use std::thread::*;
+use music::sdl2::audio::{AudioCallback, AudioDevice, AudioSpec, AudioSpecDesired, AudioSpecWAV, AudioCVT};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
 
+/// the sound files, in the order of the numbers passed to play
+const SOUND_FILES: [&str; 6] = [
+    "./assets/shoot_player.wav",
+    "./assets/lost_life.wav",
+    "./assets/enemy_lost_life.wav",
+    "./assets/shoot_enemy.wav",
+    "./assets/player_die.wav",
+    "./assets/taunt.wav",
+];
 
-struct Sound {
-    data: Vec<u8>,
+/// where play sends its sounds once start has opened the audio device
+static CUES: Mutex<Option<Sender<Cue>>> = Mutex::new(None);
+
+/// a sound to start playing and how loud
+struct Cue {
+    sound: usize,
+    volume: f32,
+}
+
+/// a sound that is playing, pos is how far into it the device is
+struct Voice {
+    sound: usize,
     volume: f32,
     pos: usize,
 }
 
-impl AudioCallback for Sound {
+/// mixes every sound that is playing into the one audio device
+/// sounds: every sound file, converted to the device format
+/// voices: the sounds playing right now
+/// cues: the sounds play asked for since the last callback
+struct Mixer {
+    sounds: Vec<Vec<u8>>,
+    voices: Vec<Voice>,
+    cues: Receiver<Cue>,
+}
+
+impl AudioCallback for Mixer {
     type Channel = u8;
 
     fn callback(&mut self, out: &mut [u8]) {
+        while let Ok(cue) = self.cues.try_recv() {
+            if cue.sound < self.sounds.len() {
+                self.voices.push(Voice { sound: cue.sound, volume: cue.volume, pos: 0 });
+            }
+        }
+        // u8 samples are silent at 128, so each sound is added as its distance from that
         for dst in out.iter_mut() {
-            *dst = (*self.data.get(self.pos).unwrap_or(&0) as f32 * self.volume) as u8;
-            self.pos += 1;
+            let mut sample = 0.0;
+            for voice in &mut self.voices {
+                if let Some(&value) = self.sounds[voice.sound].get(voice.pos) {
+                    sample += (value as f32 - 128.0) * voice.volume;
+                }
+                voice.pos += 1;
+            }
+            *dst = (sample + 128.0).clamp(0.0, 255.0) as u8;
         }
+        let sounds = &self.sounds;
+        self.voices.retain(|voice| voice.pos < sounds[voice.sound].len());
     }
 }
 
-// I tried everything i could think of to pass a string to this function and
-// I couldnt get past the lifetime errors
-/// play will play sounds depending on the u32 that is passed to the function
-/// assets are grabbed from the assets folder
-pub fn play(sound: u32) {
-
-    let player_shoot = "./assets/shoot_player.wav";
-    let player_die = "./assets/lost_life.wav";
-    let enemy_die = "./assets/enemy_lost_life.wav";
-    let enemy_shoot = "./assets/shoot_enemy.wav";
-    let player_die_final = "./assets/player_die.wav";
-
-    let file_to_play: &str;
-
-    match sound {
-        0 => file_to_play = player_shoot,
-        1=> file_to_play = player_die,
-        2=> file_to_play = enemy_die,
-        3=> file_to_play = enemy_shoot,
-        4=> file_to_play = player_die_final,
-        _ => file_to_play = player_shoot
+/// opens the audio device and loads every sound, once when the game starts
+/// the device stays on a thread of its own for as long as the game runs,
+/// until start has worked play does nothing
+pub fn start() -> Result<(), String> {
+    let (cues, receiver) = mpsc::channel();
+    let (opened, result) = mpsc::channel();
+    thread::spawn(move || {
+        match open(receiver) {
+            Ok(_device) => {
+                let _ = opened.send(Ok(()));
+                loop {
+                    thread::park();
+                }
+            }
+            Err(e) => {
+                let _ = opened.send(Err(e));
+            }
+        }
+    });
+    result.recv().map_err(|e| e.to_string())??;
+    if let Ok(mut sender) = CUES.lock() {
+        *sender = Some(cues);
     }
+    Ok(())
+}
 
-    let wav_file : Cow<'static, Path> = match args().nth(1) {
-        None => Cow::from(Path::new(file_to_play)),
-        Some(s) => Cow::from(PathBuf::from(s))
-    };
-    let sdl_context = sdl2::init().unwrap();
-
-    let audio_subsystem = sdl_context.audio().unwrap();
+/// opens the device with a mixer that plays the cues sent to it
+fn open(cues: Receiver<Cue>) -> Result<AudioDevice<Mixer>, String> {
+    let sdl_context = sdl2::init()?;
+    let audio_subsystem = sdl_context.audio()?;
     let desired_spec = AudioSpecDesired {
         freq: Some(44_100),
         channels: Some(1), // mono
         samples: None      // default
     };
-    let device = audio_subsystem.open_playback(None, &desired_spec, |spec| {
-        let wav = AudioSpecWAV::load_wav(wav_file)
-            .expect("Could not load test WAV file");
-        let cvt = AudioCVT::new(
-                wav.format, wav.channels, wav.freq,
-                spec.format, spec.channels, spec.freq)
-            .expect("Could not convert WAV file");
-        let data = cvt.convert(wav.buffer().to_vec());
-        Sound {
-            data: data,
-            volume: 0.25,
-            pos: 0,
-        }
-    }).unwrap();
+    let mut device = audio_subsystem.open_playback(None, &desired_spec, |_| {
+        Mixer { sounds: Vec::new(), voices: Vec::new(), cues }
+    })?;
+    let spec = *device.spec();
+    let sounds = SOUND_FILES.iter()
+        .map(|path| load(path, &spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    device.lock().sounds = sounds;
     device.resume();
-    sleep(Duration::from_millis(1_000));
-}
\ No newline at end of file
+    Ok(device)
+}
+
+/// loads a sound file and converts it to the format of the device
+fn load(path: &str, spec: &AudioSpec) -> Result<Vec<u8>, String> {
+    let wav = AudioSpecWAV::load_wav(path)
+        .map_err(|e| format!("could not load {}: {}", path, e))?;
+    let cvt = AudioCVT::new(
+            wav.format, wav.channels, wav.freq,
+            spec.format, spec.channels, spec.freq)
+        .map_err(|e| format!("could not convert {}: {}", path, e))?;
+    Ok(cvt.convert(wav.buffer().to_vec()))
+}
+
+/// play will play sounds depending on the u32 that is passed to the function
+/// the sound is mixed in with the ones already playing and play returns straight away
+/// volume goes from 0.0 to 1.0, at 0 nothing is played
+pub fn play(sound: u32, volume: f32) {
+    if volume <= 0.0 {
+        return
+    }
+    let sound = if (sound as usize) < SOUND_FILES.len() { sound as usize } else { 0 };
+    if let Ok(sender) = CUES.lock() {
+        if let Some(ref cues) = *sender {
+            let _ = cues.send(Cue { sound, volume: volume.min(1.0) });
+        }
+    }
+}
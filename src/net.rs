@@ -0,0 +1,468 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError};
+use std::thread;
+use std::time::Duration;
+
+use game::{Direction, Mode};
+use models::enemy::EnemyKind;
+
+/// the port used when none is given
+pub const DEFAULT_PORT: u16 = 7777;
+/// sent by both sides when connecting, the number is bumped if the messages change
+const HELLO: &str = "berzerk";
+const VERSION: u32 = 2;
+/// how long to wait for the other game's hello before giving up on it
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// messages waiting to be written, a peer that falls this far behind is dropped
+const MAX_QUEUED_MESSAGES: usize = 120;
+
+/// a connection to the other game, one message per line
+/// lines are read and written on their own threads so the game never waits on the network
+pub struct Connection {
+    stream: TcpStream,
+    incoming: Receiver<String>,
+    outgoing: SyncSender<String>,
+    closed: bool,
+}
+
+impl Connection {
+    /// waits for a player to join on the listener and checks they speak the same version
+    pub fn accept(listener: &TcpListener) -> io::Result<Connection> {
+        let (stream, _) = listener.accept()?;
+        Connection::handshake(stream, HANDSHAKE_TIMEOUT)
+    }
+
+    /// joins a game hosted at an address like `192.168.1.5:7777`
+    pub fn join<A: ToSocketAddrs>(address: A) -> io::Result<Connection> {
+        let stream = TcpStream::connect(address)?;
+        Connection::handshake(stream, HANDSHAKE_TIMEOUT)
+    }
+
+    /// both sides send `berzerk VERSION` and read the other's before anything else
+    /// fails with TimedOut if the other side sends nothing for timeout
+    fn handshake(stream: TcpStream, timeout: Duration) -> io::Result<Connection> {
+        stream.set_nodelay(true)?;
+        let mut writer = stream.try_clone()?;
+        writeln!(writer, "{} {}", HELLO, VERSION)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut hello = String::new();
+        // the timeout is on the socket, so it is taken off again before the reader thread uses it
+        stream.set_read_timeout(Some(timeout))?;
+        let read = reader.read_line(&mut hello);
+        stream.set_read_timeout(None)?;
+        match read {
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "the other game did not answer"))
+            },
+            Err(e) => return Err(e),
+            Ok(_) => (),
+        }
+        if hello.trim() != format!("{} {}", HELLO, VERSION) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                format!("the other game is not berzerk version {}: {}", VERSION, hello.trim())));
+        }
+
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                match line {
+                    Ok(line) => if sender.send(line).is_err() { break },
+                    Err(_) => break,
+                }
+            }
+        });
+        let (outgoing, queued) = mpsc::sync_channel::<String>(MAX_QUEUED_MESSAGES);
+        thread::spawn(move || {
+            for message in queued {
+                if writeln!(writer, "{}", message).is_err() {
+                    break
+                }
+            }
+        });
+        Ok(Connection {
+            stream,
+            incoming,
+            outgoing,
+            closed: false,
+        })
+    }
+
+    /// queues one message for the writer thread
+    /// a failed write or a peer that stops reading until the queue is full closes the connection
+    pub fn send(&mut self, message: &str) {
+        if self.closed {
+            return
+        }
+        match self.outgoing.try_send(message.to_string()) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+                self.closed = true;
+                let _ = self.stream.shutdown(Shutdown::Both);
+            },
+        }
+    }
+
+    /// the messages that arrived since the last call, oldest first
+    pub fn receive(&mut self) -> Vec<String> {
+        let mut messages = Vec::new();
+        loop {
+            match self.incoming.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.closed = true;
+                    break
+                },
+            }
+        }
+        messages
+    }
+
+    /// true once the other game has gone away
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+/// the reader and writer threads hold their own handles on the socket, so it is shut down
+/// here for the other game to see the connection close
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+/// what the joining player sends each update
+/// Move: the direction held on that update, seq counts up by one every update
+/// Fire: the fire key was pressed
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ClientMessage {
+    Move { seq: u64, dir: Option<Direction> },
+    Fire { seq: u64 },
+}
+
+/// the part of the game the host is showing
+/// Waiting: menus, options or initials entry on the host
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Screen {
+    Waiting,
+    Playing,
+    Paused,
+    Transition,
+    GameOver,
+    Won,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PlayerState {
+    pub x: f64,
+    pub y: f64,
+    pub dir: Direction,
+    pub health: u32,
+    pub max_health: u32,
+    pub is_moving: bool,
+    pub score: u32,
+    pub kills: u32,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct EnemyState {
+    pub x: f64,
+    pub y: f64,
+    pub dir: Direction,
+    pub kind: EnemyKind,
+}
+
+/// owner: the player who fired it, none for robot bullets
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct BulletState {
+    pub owner: Option<usize>,
+    pub x: f64,
+    pub y: f64,
+    pub dir: Direction,
+    pub speed: f64,
+}
+
+/// everything the joining game needs to draw one update of the host's game
+/// tick: the host's update number
+/// ack: the last Move seq the host used, moves after it are replayed by the joining game
+/// time_left: seconds left in a timed game, none when it is not timed
+#[derive(Clone, PartialEq, Debug)]
+pub struct Snapshot {
+    pub tick: u64,
+    pub ack: u64,
+    pub screen: Screen,
+    pub mode: Mode,
+    pub time_left: Option<f64>,
+    pub level: u32,
+    pub score: u32,
+    pub players: Vec<PlayerState>,
+    pub enemies: Vec<EnemyState>,
+    pub bullets: Vec<BulletState>,
+}
+
+pub fn dir_name(dir: Direction) -> &'static str {
+    match dir {
+        Direction::WEST => "west",
+        Direction::NORTH => "north",
+        Direction::EAST => "east",
+        Direction::SOUTH => "south",
+    }
+}
+
+pub fn dir_from_name(name: &str) -> Option<Direction> {
+    match name {
+        "west" => Some(Direction::WEST),
+        "north" => Some(Direction::NORTH),
+        "east" => Some(Direction::EAST),
+        "south" => Some(Direction::SOUTH),
+        _ => None,
+    }
+}
+
+pub fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Campaign => "campaign",
+        Mode::Endless => "endless",
+        Mode::Versus => "versus",
+    }
+}
+
+pub fn mode_from_name(name: &str) -> Option<Mode> {
+    match name {
+        "campaign" => Some(Mode::Campaign),
+        "endless" => Some(Mode::Endless),
+        "versus" => Some(Mode::Versus),
+        _ => None,
+    }
+}
+
+pub fn kind_name(kind: EnemyKind) -> &'static str {
+    match kind {
+        EnemyKind::Drone => "drone",
+        EnemyKind::Soldier => "soldier",
+        EnemyKind::Hunter => "hunter",
+    }
+}
+
+fn kind_from_name(name: &str) -> Option<EnemyKind> {
+    match name {
+        "drone" => Some(EnemyKind::Drone),
+        "soldier" => Some(EnemyKind::Soldier),
+        "hunter" => Some(EnemyKind::Hunter),
+        _ => None,
+    }
+}
+
+impl Screen {
+    fn name(&self) -> &'static str {
+        match *self {
+            Screen::Waiting => "waiting",
+            Screen::Playing => "playing",
+            Screen::Paused => "paused",
+            Screen::Transition => "transition",
+            Screen::GameOver => "game_over",
+            Screen::Won => "won",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Screen> {
+        match name {
+            "waiting" => Some(Screen::Waiting),
+            "playing" => Some(Screen::Playing),
+            "paused" => Some(Screen::Paused),
+            "transition" => Some(Screen::Transition),
+            "game_over" => Some(Screen::GameOver),
+            "won" => Some(Screen::Won),
+            _ => None,
+        }
+    }
+}
+
+impl ClientMessage {
+    /// `move SEQ DIR` with none for standing still, or `fire SEQ`
+    pub fn to_line(&self) -> String {
+        match *self {
+            ClientMessage::Move { seq, dir } => format!("move {} {}", seq, dir.map_or("none", dir_name)),
+            ClientMessage::Fire { seq } => format!("fire {}", seq),
+        }
+    }
+
+    pub fn parse(line: &str) -> Option<ClientMessage> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match (parts.first().cloned(), parts.len()) {
+            (Some("move"), 3) => {
+                let dir = if parts[2] == "none" { None } else { Some(dir_from_name(parts[2])?) };
+                Some(ClientMessage::Move { seq: parts[1].parse().ok()?, dir })
+            },
+            (Some("fire"), 2) => Some(ClientMessage::Fire { seq: parts[1].parse().ok()? }),
+            _ => None,
+        }
+    }
+}
+
+impl Snapshot {
+    /// the snapshot on one line, records are separated by `;`
+    /// `snapshot TICK ACK SCREEN MODE TIME_LEFT LEVEL SCORE` comes first, with none for an untimed game,
+    /// followed by `player X Y DIR HEALTH MAX_HEALTH MOVING SCORE KILLS`, `enemy X Y DIR KIND`
+    /// and `bullet OWNER X Y DIR SPEED` with robot for the owner of robot bullets
+    pub fn to_line(&self) -> String {
+        let time_left = self.time_left.map_or("none".to_string(), |time_left| time_left.to_string());
+        let mut records = vec![format!("snapshot {} {} {} {} {} {} {}",
+            self.tick, self.ack, self.screen.name(), mode_name(self.mode), time_left, self.level, self.score)];
+        for p in &self.players {
+            records.push(format!("player {} {} {} {} {} {} {} {}",
+                p.x, p.y, dir_name(p.dir), p.health, p.max_health, p.is_moving, p.score, p.kills));
+        }
+        for e in &self.enemies {
+            records.push(format!("enemy {} {} {} {}", e.x, e.y, dir_name(e.dir), kind_name(e.kind)));
+        }
+        for b in &self.bullets {
+            let owner = b.owner.map_or("robot".to_string(), |owner| owner.to_string());
+            records.push(format!("bullet {} {} {} {} {}", owner, b.x, b.y, dir_name(b.dir), b.speed));
+        }
+        records.join(";")
+    }
+
+    pub fn parse(line: &str) -> Option<Snapshot> {
+        let mut records = line.split(';').map(|record| record.split_whitespace().collect::<Vec<&str>>());
+        let first = records.next()?;
+        let mut snapshot = match (first.first().cloned(), first.len()) {
+            (Some("snapshot"), 8) => Snapshot {
+                tick: first[1].parse().ok()?,
+                ack: first[2].parse().ok()?,
+                screen: Screen::from_name(first[3])?,
+                mode: mode_from_name(first[4])?,
+                time_left: if first[5] == "none" { None } else { Some(first[5].parse().ok()?) },
+                level: first[6].parse().ok()?,
+                score: first[7].parse().ok()?,
+                players: Vec::new(),
+                enemies: Vec::new(),
+                bullets: Vec::new(),
+            },
+            _ => return None,
+        };
+        for r in records {
+            match (r.first().cloned(), r.len()) {
+                (Some("player"), 9) => snapshot.players.push(PlayerState {
+                    x: r[1].parse().ok()?,
+                    y: r[2].parse().ok()?,
+                    dir: dir_from_name(r[3])?,
+                    health: r[4].parse().ok()?,
+                    max_health: r[5].parse().ok()?,
+                    is_moving: r[6].parse().ok()?,
+                    score: r[7].parse().ok()?,
+                    kills: r[8].parse().ok()?,
+                }),
+                (Some("enemy"), 5) => snapshot.enemies.push(EnemyState {
+                    x: r[1].parse().ok()?,
+                    y: r[2].parse().ok()?,
+                    dir: dir_from_name(r[3])?,
+                    kind: kind_from_name(r[4])?,
+                }),
+                (Some("bullet"), 6) => snapshot.bullets.push(BulletState {
+                    owner: if r[1] == "robot" { None } else { Some(r[1].parse().ok()?) },
+                    x: r[2].parse().ok()?,
+                    y: r[3].parse().ok()?,
+                    dir: dir_from_name(r[4])?,
+                    speed: r[5].parse().ok()?,
+                }),
+                _ => return None,
+            }
+        }
+        Some(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_client_message_round_trip() {
+        let messages = [
+            ClientMessage::Move { seq: 12, dir: Some(Direction::NORTH) },
+            ClientMessage::Move { seq: 13, dir: None },
+            ClientMessage::Fire { seq: 14 },
+        ];
+        for message in messages.iter() {
+            assert!(ClientMessage::parse(&message.to_line()) == Some(*message));
+        }
+        assert!(ClientMessage::parse("move 1 up").is_none());
+        assert!(ClientMessage::parse("jump 1").is_none());
+    }
+
+    #[test]
+    fn test_handshake_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let _silent = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        match Connection::handshake(stream, Duration::from_millis(50)) {
+            Err(e) => assert!(e.kind() == io::ErrorKind::TimedOut),
+            Ok(_) => panic!("the handshake should time out"),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let snapshot = Snapshot {
+            tick: 300,
+            ack: 280,
+            screen: Screen::Playing,
+            mode: Mode::Versus,
+            time_left: Some(42.5),
+            level: 2,
+            score: 150,
+            players: vec![PlayerState { x: 75.5, y: 300.0, dir: Direction::EAST, health: 3, max_health: 4,
+                is_moving: true, score: 100, kills: 2 }],
+            enemies: vec![EnemyState { x: 0.1, y: 2.0 / 3.0, dir: Direction::WEST, kind: EnemyKind::Hunter }],
+            bullets: vec![
+                BulletState { owner: Some(0), x: 1.0, y: 2.0, dir: Direction::SOUTH, speed: 5.0 },
+                BulletState { owner: None, x: 3.0, y: 4.0, dir: Direction::NORTH, speed: 6.2 },
+            ],
+        };
+        assert!(Snapshot::parse(&snapshot.to_line()) == Some(snapshot));
+        assert!(Snapshot::parse("snapshot 1 0 playing campaign none 1 0").is_some());
+        assert!(Snapshot::parse("snapshot 1 0 playing campaign none 1 0;enemy 1 2").is_none());
+    }
+
+    #[test]
+    fn test_connection_on_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let joining = thread::spawn(move || Connection::join(address).unwrap());
+        let mut host = Connection::accept(&listener).unwrap();
+        let mut client = joining.join().unwrap();
+
+        client.send("fire 1");
+        let mut received = Vec::new();
+        while received.is_empty() {
+            received = host.receive();
+        }
+        assert!(received == vec!["fire 1".to_string()]);
+
+        drop(client);
+        while !host.is_closed() {
+            host.receive();
+        }
+    }
+
+    #[test]
+    fn test_peer_that_stops_reading_is_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut stalled = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        writeln!(stalled, "{} {}", HELLO, VERSION).unwrap();
+        let mut host = Connection::accept(&listener).unwrap();
+
+        // the socket buffers fill up, then the queue, and send never blocks
+        let message = "x".repeat(1000);
+        for _ in 0..1_000_000 {
+            host.send(&message);
+            if host.is_closed() {
+                break
+            }
+        }
+        assert!(host.is_closed());
+    }
+}
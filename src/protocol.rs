@@ -0,0 +1,324 @@
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use campaign::Campaign;
+use difficulty::Difficulty;
+use environment::{Action, Environment, Observation, ACTION_COUNT};
+use game::{Mode, FPS};
+use net::{dir_from_name, dir_name, kind_name, mode_from_name};
+use serde_json::{self, Value};
+use settings::Settings;
+
+/// sent in the hello line, bumped when the messages change
+pub const VERSION: u32 = 1;
+
+/// the first line an agent sends, every field can be left out
+/// seed: decides where the robots go and what they do, 0 by default
+/// mode: `campaign` or `endless`
+/// difficulty: `easy`, `normal` or `hard`, the settings file's by default
+/// lockstep: when true the game waits for an action before each update,
+/// otherwise it runs at normal speed using the newest action it has
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Handshake {
+    pub seed: u64,
+    pub mode: Mode,
+    pub difficulty: Option<Difficulty>,
+    pub lockstep: bool,
+}
+
+/// a line from the agent after the handshake
+/// Act: `{"dir": "north", "fire": true}` or `{"action": N}` with N from 0 to 9
+/// Reset: `{"reset": SEED}` starts a new game
+/// Quit: `{"quit": true}` ends the session, so does closing stdin
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Command {
+    Act(Action),
+    Reset(u64),
+    Quit,
+}
+
+impl Handshake {
+    pub fn parse(line: &str) -> Result<Handshake, String> {
+        let value = parse(line)?;
+        let mut handshake = Handshake { seed: 0, mode: Mode::Campaign, difficulty: None, lockstep: false };
+        if let Some(seed) = value.get("seed") {
+            handshake.seed = seed.as_u64().ok_or("seed must be a whole number")?;
+        }
+        if let Some(mode) = value.get("mode") {
+            handshake.mode = match mode.as_str().and_then(mode_from_name) {
+                Some(Mode::Versus) | None => return Err("mode must be campaign or endless".to_string()),
+                Some(mode) => mode,
+            };
+        }
+        if let Some(difficulty) = value.get("difficulty") {
+            handshake.difficulty = Some(difficulty.as_str().and_then(Difficulty::from_name)
+                .ok_or("difficulty must be easy, normal or hard")?);
+        }
+        if let Some(lockstep) = value.get("lockstep") {
+            handshake.lockstep = lockstep.as_bool().ok_or("lockstep must be true or false")?;
+        }
+        Ok(handshake)
+    }
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Command, String> {
+        let value = parse(line)?;
+        if value.get("quit").and_then(Value::as_bool) == Some(true) {
+            return Ok(Command::Quit)
+        }
+        if let Some(seed) = value.get("reset") {
+            return seed.as_u64().map(Command::Reset).ok_or("reset needs a seed".to_string())
+        }
+        if let Some(index) = value.get("action") {
+            return match index.as_u64() {
+                Some(n) if n < ACTION_COUNT as u64 => Ok(Command::Act(Action::from_index(n as usize))),
+                _ => Err(format!("action must be a number from 0 to {}", ACTION_COUNT - 1)),
+            }
+        }
+        let dir = match value.get("dir") {
+            None | Some(&Value::Null) => None,
+            Some(dir) => Some(dir.as_str().and_then(dir_from_name).ok_or("dir must be north, south, east, west or null")?),
+        };
+        let fire = match value.get("fire") {
+            None => false,
+            Some(fire) => fire.as_bool().ok_or("fire must be true or false")?,
+        };
+        Ok(Command::Act(Action { dir, fire }))
+    }
+}
+
+/// reads one line from the agent
+fn parse(line: &str) -> Result<Value, String> {
+    serde_json::from_str(line).map_err(|e| e.to_string())
+}
+
+fn error(message: &str) -> Value {
+    json!({"type": "error", "message": message})
+}
+
+fn hello(handshake: &Handshake) -> Value {
+    json!({
+        "type": "hello",
+        "version": VERSION,
+        "seed": handshake.seed,
+        "lockstep": handshake.lockstep,
+        "actions": ACTION_COUNT,
+        "fps": FPS,
+    })
+}
+
+/// one update of the game as the agent sees it
+fn state(tick: u64, observation: &Observation, reward: f64, done: bool) -> Value {
+    let players: Vec<Value> = observation.players.iter().map(|p| json!({
+        "x": p.x,
+        "y": p.y,
+        "dir": dir_name(p.dir),
+        "health": p.health,
+        "moving": p.is_moving,
+    })).collect();
+    let enemies: Vec<Value> = observation.enemies.iter().map(|e| json!({
+        "x": e.x,
+        "y": e.y,
+        "dir": dir_name(e.dir),
+        "kind": kind_name(e.kind),
+    })).collect();
+    let bullets: Vec<Value> = observation.bullets.iter().map(|b| json!({
+        "owner": b.owner,
+        "x": b.x,
+        "y": b.y,
+        "dir": dir_name(b.dir),
+        "speed": b.speed,
+    })).collect();
+    json!({
+        "type": "state",
+        "tick": tick,
+        "reward": reward,
+        "done": done,
+        "score": observation.score,
+        "level": observation.level,
+        "players": players,
+        "enemies": enemies,
+        "bullets": bullets,
+        "walls": observation.walls,
+    })
+}
+
+fn send<W: Write>(output: &mut W, value: &Value) -> Result<(), String> {
+    writeln!(output, "{}", value).and_then(|_| output.flush()).map_err(|e| e.to_string())
+}
+
+/// plays games for an agent that reads state lines from the output and writes commands to the input,
+/// one JSON object per line
+/// the agent sends the handshake, the game answers with a hello line and the first state,
+/// then a state line follows every update until the agent quits or closes the input
+pub fn run<R, W>(mut settings: Settings, campaign: Campaign, mut input: R, output: &mut W) -> Result<(), String>
+    where R: BufRead + Send + 'static, W: Write
+{
+    let mut line = String::new();
+    input.read_line(&mut line).map_err(|e| e.to_string())?;
+    let handshake = match Handshake::parse(&line) {
+        Ok(handshake) => handshake,
+        Err(e) => {
+            send(output, &error(&e))?;
+            return Err(e)
+        },
+    };
+    if let Some(difficulty) = handshake.difficulty {
+        settings.gameplay.difficulty = difficulty;
+    }
+    let mut env = Environment::new(settings, campaign, handshake.mode);
+    send(output, &hello(&handshake))?;
+    let observation = env.reset(handshake.seed);
+    send(output, &state(0, &observation, 0.0, false))?;
+
+    if handshake.lockstep {
+        run_lockstep(&mut env, input, output)
+    } else {
+        run_real_time(&mut env, input, output)
+    }
+}
+
+/// one update for every action, the game never moves on without the agent
+fn run_lockstep<R: BufRead, W: Write>(env: &mut Environment, input: R, output: &mut W) -> Result<(), String> {
+    let mut tick = 0;
+    for line in input.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue
+        }
+        match Command::parse(&line) {
+            Ok(Command::Act(action)) => {
+                let (observation, reward, done) = env.step(action);
+                tick += 1;
+                send(output, &state(tick, &observation, reward, done))?;
+            },
+            Ok(Command::Reset(seed)) => {
+                let observation = env.reset(seed);
+                tick = 0;
+                send(output, &state(tick, &observation, 0.0, false))?;
+            },
+            Ok(Command::Quit) => break,
+            Err(e) => send(output, &error(&e))?,
+        }
+    }
+    Ok(())
+}
+
+/// sixty updates a second whether the agent keeps up or not
+/// the direction is held until the agent sends another and every fire shoots once
+/// once a game is over nothing more is sent until the agent resets or quits
+fn run_real_time<R, W>(env: &mut Environment, input: R, output: &mut W) -> Result<(), String>
+    where R: BufRead + Send + 'static, W: Write
+{
+    let (sender, incoming) = mpsc::channel();
+    thread::spawn(move || {
+        for line in input.lines() {
+            match line {
+                Ok(line) => if sender.send(line).is_err() { break },
+                Err(_) => break,
+            }
+        }
+    });
+
+    let frame = Duration::from_millis(1000 / FPS);
+    let mut tick = 0;
+    let mut held = Action::default();
+    let mut done = false;
+    loop {
+        let frame_start = Instant::now();
+        let mut fire = false;
+        loop {
+            let line = match incoming.try_recv() {
+                Ok(line) => line,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            };
+            if line.trim().is_empty() {
+                continue
+            }
+            match Command::parse(&line) {
+                Ok(Command::Act(action)) => {
+                    held = action;
+                    fire = fire || action.fire;
+                },
+                Ok(Command::Reset(seed)) => {
+                    let observation = env.reset(seed);
+                    tick = 0;
+                    held = Action::default();
+                    fire = false;
+                    done = false;
+                    send(output, &state(tick, &observation, 0.0, false))?;
+                },
+                Ok(Command::Quit) => return Ok(()),
+                Err(e) => send(output, &error(&e))?,
+            }
+        }
+        if !done {
+            let (observation, reward, over) = env.step(Action { dir: held.dir, fire });
+            tick += 1;
+            done = over;
+            send(output, &state(tick, &observation, reward, done))?;
+        }
+        let elapsed = frame_start.elapsed();
+        if elapsed < frame {
+            thread::sleep(frame - elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use std::io::Cursor;
+    use game::Direction;
+
+    #[test]
+    fn test_handshake() {
+        let handshake = Handshake::parse(r#"{"seed": 9, "mode": "endless", "difficulty": "hard", "lockstep": true}"#).unwrap();
+        assert!(handshake == Handshake { seed: 9, mode: Mode::Endless, difficulty: Some(Difficulty::Hard), lockstep: true });
+        assert!(Handshake::parse("{}").unwrap() == Handshake { seed: 0, mode: Mode::Campaign, difficulty: None, lockstep: false });
+        assert!(Handshake::parse(r#"{"mode": "versus"}"#).is_err());
+        assert!(Handshake::parse(r#"{"seed": "nine"}"#).is_err());
+        assert!(Handshake::parse(r#"{"seed": -1}"#).is_err());
+    }
+
+    #[test]
+    fn test_commands() {
+        assert!(Command::parse(r#"{"dir": "north", "fire": true}"#) == Ok(Command::Act(Action { dir: Some(Direction::NORTH), fire: true })));
+        assert!(Command::parse(r#"{"dir": null}"#) == Ok(Command::Act(Action::default())));
+        assert!(Command::parse(r#"{"action": 8}"#) == Ok(Command::Act(Action::from_index(8))));
+        assert!(Command::parse(r#"{"reset": 4}"#) == Ok(Command::Reset(4)));
+        assert!(Command::parse(r#"{"quit": true}"#) == Ok(Command::Quit));
+        assert!(Command::parse(r#"{"action": 10}"#).is_err());
+        assert!(Command::parse(r#"{"dir": "up"}"#).is_err());
+    }
+
+    #[test]
+    fn test_lockstep_session() {
+        let input = Cursor::new(concat!(
+            "{\"seed\": 2, \"lockstep\": true}\n",
+            "{\"dir\": \"south\"}\n",
+            "{\"jump\": 1, \"dir\": 5}\n",
+            "{\"action\": 0}\n",
+            "{\"quit\": true}\n",
+            "{\"action\": 0}\n",
+        ).as_bytes().to_vec());
+        let mut output = Vec::new();
+        run(Settings::default(), Campaign::default(), input, &mut output).unwrap();
+        let lines: Vec<Value> = String::from_utf8(output).unwrap().lines().map(|line| parse(line).unwrap()).collect();
+        let types: Vec<&str> = lines.iter().filter_map(|line| line["type"].as_str()).collect();
+        assert!(types == vec!["hello", "state", "state", "error", "state"]);
+        assert!(lines[4]["tick"] == 2);
+    }
+
+    #[test]
+    fn test_bad_handshake() {
+        let mut output = Vec::new();
+        let result = run(Settings::default(), Campaign::default(), Cursor::new(b"hello\n".to_vec()), &mut output);
+        assert!(result.is_err());
+        assert!(String::from_utf8(output).unwrap().starts_with("{\"type\":\"error\""));
+    }
+}
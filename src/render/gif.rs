@@ -0,0 +1,187 @@
+//! writes animated GIFs of images, frame by frame so a recording never has to be held in memory
+//! after the first frame only the part that changed is written
+//! each frame gets its own palette of up to 256 of its colours
+
+extern crate gif;
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use render::software::Image;
+
+/// a GIF being written to w, frames must all be the size it was started with
+/// the end of the file is written when it is dropped
+/// previous: the last frame, only the part of the next one that differs from it is written
+pub struct GifWriter<W: Write> {
+    encoder: gif::Encoder<W>,
+    width: u16,
+    height: u16,
+    previous: Option<Image>,
+}
+
+/// [x, y, width, height] of the smallest rectangle holding every pixel that differs,
+/// a single pixel when none do as a frame can not be empty
+fn changed_area(old: &Image, new: &Image) -> [u32; 4] {
+    let (mut left, mut top, mut right, mut bottom) = (new.width, new.height, 0, 0);
+    for y in 0..new.height {
+        for x in 0..new.width {
+            if old.pixel(x, y) != new.pixel(x, y) {
+                left = left.min(x);
+                top = top.min(y);
+                right = right.max(x + 1);
+                bottom = bottom.max(y + 1);
+            }
+        }
+    }
+    if right == 0 {
+        [0, 0, 1, 1]
+    } else {
+        [left, top, right - left, bottom - top]
+    }
+}
+
+/// the part of an image inside [x, y, width, height]
+fn crop(image: &Image, area: [u32; 4]) -> Image {
+    let mut cropped = Image::new(area[2], area[3]);
+    let row = area[2] as usize * 4;
+    for y in 0..area[3] as usize {
+        let start = ((area[1] as usize + y) * image.width as usize + area[0] as usize) * 4;
+        cropped.pixels[y * row..(y + 1) * row].copy_from_slice(&image.pixels[start..start + row]);
+    }
+    cropped
+}
+
+/// the colours of a frame and the index of each pixel's colour
+/// a frame with more than 256 colours keeps the most used ones and the rest take the nearest of those
+fn palette(image: &Image) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let mut counts: HashMap<[u8; 3], u32> = HashMap::new();
+    for pixel in image.pixels.chunks(4) {
+        *counts.entry([pixel[0], pixel[1], pixel[2]]).or_insert(0) += 1;
+    }
+    let mut used: Vec<([u8; 3], u32)> = counts.into_iter().collect();
+    // ties are broken by the colour so the same frame always gets the same palette
+    used.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let colors: Vec<[u8; 3]> = used.iter().take(256).map(|&(color, _)| color).collect();
+    let mut lookup: HashMap<[u8; 3], u8> = colors.iter().enumerate().map(|(i, &color)| (color, i as u8)).collect();
+    let indices = image.pixels.chunks(4)
+        .map(|pixel| {
+            let color = [pixel[0], pixel[1], pixel[2]];
+            *lookup.entry(color).or_insert_with(|| nearest(&colors, color))
+        })
+        .collect();
+    (colors, indices)
+}
+
+/// the index of the colour closest to color
+fn nearest(colors: &[[u8; 3]], color: [u8; 3]) -> u8 {
+    let distance = |other: &[u8; 3]| (0..3).map(|c| (other[c] as i32 - color[c] as i32).pow(2)).sum::<i32>();
+    (0..colors.len()).min_by_key(|&i| distance(&colors[i])).unwrap_or(0) as u8
+}
+
+impl<W: Write> GifWriter<W> {
+    /// writes the header, the GIF loops forever
+    pub fn new(out: W, width: u32, height: u32) -> io::Result<Self> {
+        let (width, height) = (width.min(0xffff) as u16, height.min(0xffff) as u16);
+        let mut encoder = gif::Encoder::new(out, width, height, &[]).map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+        Ok(GifWriter { encoder, width, height, previous: None })
+    }
+
+    /// adds a frame shown for delay hundredths of a second
+    pub fn frame(&mut self, image: &Image, delay: u16) -> io::Result<()> {
+        if image.width != self.width as u32 || image.height != self.height as u32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "every frame has to be the same size"))
+        }
+        let area = match self.previous {
+            Some(ref previous) => changed_area(previous, image),
+            None => [0, 0, image.width, image.height],
+        };
+        let (colors, indices) = palette(&crop(image, area));
+        let colors: Vec<u8> = colors.iter().flat_map(|color| color.iter().cloned()).collect();
+        let mut frame = gif::Frame::from_palette_pixels(area[2] as u16, area[3] as u16, indices, colors, None);
+        frame.left = area[0] as u16;
+        frame.top = area[1] as u16;
+        frame.delay = delay;
+        // each frame is left in place for the next to be drawn over
+        frame.dispose = gif::DisposalMethod::Keep;
+        self.encoder.write_frame(&frame).map_err(io::Error::other)?;
+        self.previous = Some(image.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    /// the frames of a GIF as the gif crate reads them
+    fn frames(bytes: &[u8]) -> Vec<gif::Frame<'static>> {
+        let mut reader = gif::Decoder::new(bytes).unwrap();
+        let mut frames = Vec::new();
+        while let Some(frame) = reader.read_next_frame().unwrap() {
+            frames.push(frame.clone());
+        }
+        frames
+    }
+
+    #[test]
+    fn test_palette_keeps_the_most_used_colours() {
+        let mut image = Image::new(2, 1);
+        image.pixels = vec![10, 20, 30, 255, 200, 100, 0, 255];
+        let (colors, indices) = palette(&image);
+        assert!(colors == vec![[10, 20, 30], [200, 100, 0]] && indices == vec![0, 1]);
+        let mut busy = Image::new(400, 1);
+        for (i, pixel) in busy.pixels.chunks_mut(4).enumerate() {
+            let color = if i < 100 { [128, 153, 179, 255] } else { [i as u8, (i / 256) as u8, 0, 255] };
+            pixel.copy_from_slice(&color);
+        }
+        let (colors, indices) = palette(&busy);
+        assert!(colors.len() == 256 && colors[0] == [128, 153, 179] && indices[0] == 0);
+        // the last colour is one of the ones left out, it takes its nearest neighbour
+        assert!(colors[indices[255] as usize] == [210, 0, 0]);
+    }
+
+    #[test]
+    fn test_gif_layout() {
+        let mut image = Image::new(3, 2);
+        image.pixels = vec![255; 24];
+        let mut bytes = Vec::new();
+        {
+            let mut gif = GifWriter::new(&mut bytes, 3, 2).unwrap();
+            gif.frame(&image, 5).unwrap();
+            assert!(gif.frame(&Image::new(2, 2), 5).is_err());
+        }
+        assert!(&bytes[..6] == b"GIF89a" && bytes[6] == 3 && bytes[8] == 2);
+        assert!(bytes[bytes.len() - 1] == 0x3b);
+        let frames = frames(&bytes);
+        assert!(frames.len() == 1 && frames[0].delay == 5);
+        assert!(frames[0].buffer.iter().all(|&index| index == 0));
+        assert!(frames[0].palette.as_ref().map(|palette| palette[..3].to_vec()) == Some(vec![255, 255, 255]));
+    }
+
+    #[test]
+    fn test_only_changes_are_written() {
+        let mut old = Image::new(5, 4);
+        let mut new = old.clone();
+        assert!(changed_area(&old, &new) == [0, 0, 1, 1]);
+        new.blend(1, 2, [1.0, 1.0, 1.0, 1.0]);
+        new.blend(3, 1, [1.0, 1.0, 1.0, 1.0]);
+        assert!(changed_area(&old, &new) == [1, 1, 3, 2]);
+        let cropped = crop(&new, [1, 1, 3, 2]);
+        assert!(cropped.pixel(2, 0) == [255, 255, 255, 255] && cropped.pixel(0, 1) == [255, 255, 255, 255]);
+        assert!(cropped.pixel(0, 0) == [0, 0, 0, 0]);
+        old.blend(0, 0, [1.0, 0.0, 0.0, 1.0]);
+        let mut bytes = Vec::new();
+        {
+            let mut gif = GifWriter::new(&mut bytes, 5, 4).unwrap();
+            gif.frame(&old, 5).unwrap();
+            gif.frame(&old, 5).unwrap();
+            gif.frame(&new, 5).unwrap();
+        }
+        let frames = frames(&bytes);
+        assert!(frames.len() == 3);
+        assert!((frames[0].width, frames[0].height) == (5, 4));
+        assert!((frames[1].width, frames[1].height) == (1, 1));
+        assert!((frames[2].left, frames[2].top, frames[2].width, frames[2].height) == (0, 0, 4, 3));
+    }
+}
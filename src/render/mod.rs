@@ -0,0 +1,110 @@
+pub mod font;
+pub mod gif;
+pub mod opengl;
+pub mod software;
+pub mod terminal;
+
+pub use self::opengl::GlRenderer;
+pub use self::software::{Image, SoftwareRenderer, SpriteImages};
+pub use self::terminal::TerminalRenderer;
+
+/// the images the game draws, each backend keeps its own copy of them
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Sprite {
+    Player,
+    PlayerMove,
+    Heart,
+    Enemy,
+}
+
+/// everything the game needs to draw a frame, so it can be drawn by OpenGL or by anything else
+/// positions are in logical units moved and scaled by the view, colours are [r, g, b, a] from 0 to 1
+pub trait Renderer {
+    /// fills the whole window, the view does not apply
+    fn clear(&mut self, color: [f32; 4]);
+
+    /// everything drawn after is scaled by scale and moved so the logical origin lands on offset in the window
+    /// offset [0, 0] with scale 1 draws in window pixels
+    fn set_view(&mut self, offset: [f64; 2], scale: f64);
+
+    /// fills [x, y, width, height]
+    fn rectangle(&mut self, color: [f32; 4], rect: [f64; 4]);
+
+    /// draws an image at its own size with its top left corner at x, y, multiplied by color
+    fn sprite(&mut self, sprite: Sprite, color: [f32; 4], x: f64, y: f64);
+
+    /// writes one line of text in the game font, x, y is the left end of the baseline and size is in pixels
+    fn text(&mut self, color: [f32; 4], size: u32, text: &str, x: f64, y: f64);
+
+    /// a straight line from x0, y0 to x1, y1
+    fn line(&mut self, color: [f32; 4], width: f64, line: [f64; 4]);
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use campaign::Campaign;
+    use game::{Game, Mode, State, LOGICAL_SIZE};
+    use highscores::HighScores;
+    use settings::Settings;
+
+    /// keeps what it was asked to draw instead of drawing it
+    #[derive(Default)]
+    struct Recorder {
+        view: ([f64; 2], f64),
+        rectangles: Vec<[f64; 4]>,
+        sprites: Vec<(Sprite, f64, f64)>,
+        texts: Vec<String>,
+        clears: usize,
+    }
+
+    impl Renderer for Recorder {
+        fn clear(&mut self, _color: [f32; 4]) {
+            self.clears += 1;
+        }
+
+        fn set_view(&mut self, offset: [f64; 2], scale: f64) {
+            self.view = (offset, scale);
+        }
+
+        fn rectangle(&mut self, _color: [f32; 4], rect: [f64; 4]) {
+            let (offset, scale) = self.view;
+            self.rectangles.push([offset[0] + rect[0] * scale, offset[1] + rect[1] * scale, rect[2] * scale, rect[3] * scale]);
+        }
+
+        fn sprite(&mut self, sprite: Sprite, _color: [f32; 4], x: f64, y: f64) {
+            self.sprites.push((sprite, x, y));
+        }
+
+        fn text(&mut self, _color: [f32; 4], _size: u32, text: &str, _x: f64, _y: f64) {
+            self.texts.push(text.to_string());
+        }
+
+        fn line(&mut self, _color: [f32; 4], _width: f64, _line: [f64; 4]) {}
+    }
+
+    #[test]
+    fn test_game_draws_through_renderer() {
+        let mut game = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        game.start_seeded(Mode::Campaign, 3);
+        assert!(game.state() == State::Playing);
+        let mut recorder = Recorder::default();
+        game.draw(&mut recorder, LOGICAL_SIZE);
+        assert!(recorder.clears == 1);
+        assert!(recorder.rectangles.len() == game.walls.len());
+        assert!(recorder.sprites.iter().filter(|&&(sprite, _, _)| sprite == Sprite::Enemy).count() == 6);
+        assert!(recorder.sprites.iter().filter(|&&(sprite, _, _)| sprite == Sprite::Heart).count() == 3);
+        assert!(recorder.sprites.iter().any(|&(sprite, x, _)| sprite == Sprite::Player && x == 75.0 - 10.0));
+        assert!(recorder.texts.contains(&"0".to_string()) && recorder.texts.contains(&"1".to_string()));
+    }
+
+    #[test]
+    fn test_wide_window_gets_bars() {
+        let mut game = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        let mut recorder = Recorder::default();
+        game.draw(&mut recorder, [1600.0, 600.0]);
+        assert!(recorder.texts.contains(&"BERZERK".to_string()));
+        let bars = &recorder.rectangles[recorder.rectangles.len() - 2..];
+        assert!(bars == &[[0.0, 0.0, 350.0, 600.0], [1250.0, 0.0, 350.0, 600.0]][..]);
+    }
+}
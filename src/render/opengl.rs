@@ -0,0 +1,64 @@
+extern crate graphics;
+extern crate opengl_graphics;
+
+use self::graphics::{Context, Image, Transformed};
+use self::graphics::math::Matrix2d;
+use opengl_graphics::GlGraphics;
+use opengl_graphics::GlyphCache;
+use render::{Renderer, Sprite};
+use sprites::Sprites;
+
+/// draws with OpenGL into the window a frame was started for
+/// made inside `GlGraphics::draw` from the context it hands over and dropped when the frame is done
+pub struct GlRenderer<'a, 'b: 'a> {
+    context: Context,
+    transform: Matrix2d,
+    gl: &'a mut GlGraphics,
+    glyph_cache: &'a mut GlyphCache<'b>,
+    sprites: &'a Sprites,
+}
+
+impl<'a, 'b> GlRenderer<'a, 'b> {
+    pub fn new(context: Context, gl: &'a mut GlGraphics, glyph_cache: &'a mut GlyphCache<'b>, sprites: &'a Sprites) -> Self {
+        GlRenderer {
+            context,
+            transform: context.transform,
+            gl,
+            glyph_cache,
+            sprites,
+        }
+    }
+}
+
+impl<'a, 'b> Renderer for GlRenderer<'a, 'b> {
+    fn clear(&mut self, color: [f32; 4]) {
+        graphics::clear(color, self.gl);
+    }
+
+    fn set_view(&mut self, offset: [f64; 2], scale: f64) {
+        self.transform = self.context.transform.trans(offset[0], offset[1]).scale(scale, scale);
+    }
+
+    fn rectangle(&mut self, color: [f32; 4], rect: [f64; 4]) {
+        graphics::rectangle(color, rect, self.transform, self.gl);
+    }
+
+    fn sprite(&mut self, sprite: Sprite, color: [f32; 4], x: f64, y: f64) {
+        let texture = match sprite {
+            Sprite::Player => &self.sprites.player,
+            Sprite::PlayerMove => &self.sprites.player_move,
+            Sprite::Heart => &self.sprites.heart,
+            Sprite::Enemy => &self.sprites.enemy,
+        };
+        Image::new_color(color).draw(texture, &self.context.draw_state, self.transform.trans(x, y), self.gl);
+    }
+
+    fn text(&mut self, color: [f32; 4], size: u32, text: &str, x: f64, y: f64) {
+        // a character the font can not draw is left out rather than stopping the frame
+        let _ = graphics::text(color, size, text, self.glyph_cache, self.transform.trans(x, y), self.gl);
+    }
+
+    fn line(&mut self, color: [f32; 4], width: f64, line: [f64; 4]) {
+        graphics::line(color, width / 2.0, line, self.transform, self.gl);
+    }
+}
@@ -0,0 +1,352 @@
+extern crate find_folder;
+extern crate png;
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Cursor, Write};
+use std::path::Path;
+
+use game::Game;
+use render::font::{self, ADVANCE, GLYPH_HEIGHT, GLYPH_WIDTH};
+use render::{Renderer, Sprite};
+
+/// text is drawn this many times narrower than its font size for each column of a glyph,
+/// close to the width of the game font
+const TEXT_COLUMN: f64 = 14.0;
+/// and this many times shorter for each row, so capitals stand 0.7 of the font size tall
+const TEXT_ROW: f64 = 10.0;
+
+/// an RGBA picture held in memory
+/// pixels: 4 bytes for each pixel, row after row from the top left
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// the game's sprites read into memory, for renderers that draw without a graphics card
+pub struct SpriteImages {
+    pub player: Image,
+    pub player_move: Image,
+    pub heart: Image,
+    pub enemy: Image,
+}
+
+/// draws into an image on the CPU, with no window or OpenGL context needed
+/// offset & scale: the view set by set_view
+pub struct SoftwareRenderer<'a> {
+    image: Image,
+    sprites: &'a SpriteImages,
+    offset: [f64; 2],
+    scale: f64,
+}
+
+/// an 8 bit channel from a 0 to 1 colour channel
+fn to_byte(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl Image {
+    /// a transparent black image
+    pub fn new(width: u32, height: u32) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Image::from_png(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// reads an 8 or 16 bit PNG of any colour type into RGBA
+    pub fn from_png(bytes: &[u8]) -> Result<Self, String> {
+        let mut decoder = png::Decoder::new(Cursor::new(bytes));
+        // palettes and bit depths are expanded to 8 bit greys or colours, with or without alpha
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut data = vec![0; reader.output_buffer_size().ok_or("the image is too large")?];
+        let info = reader.next_frame(&mut data).map_err(|e| e.to_string())?;
+        data.truncate(info.buffer_size());
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => data,
+            png::ColorType::Rgb => data.chunks(3).flat_map(|p| vec![p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => data.chunks(2).flat_map(|p| vec![p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => data.iter().flat_map(|&g| vec![g, g, g, 255]).collect(),
+            png::ColorType::Indexed => return Err("the palette was not expanded".to_string()),
+        };
+        Ok(Image { width: info.width, height: info.height, pixels })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut out = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?);
+        self.write_png(&mut out).and_then(|_| out.flush().map_err(|e| e.to_string()))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        self.write_png(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// writes the image as an 8 bit RGBA PNG
+    fn write_png<W: Write>(&self, out: &mut W) -> Result<(), String> {
+        let mut encoder = png::Encoder::new(out, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        // the end of the file is written when the writer is dropped
+        writer.write_image_data(&self.pixels).map_err(|e| e.to_string())
+    }
+
+    /// the RGBA of the pixel at x, y
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    /// lays the colour over the pixel at x, y as far as its alpha lets it, pixels outside are ignored
+    pub fn blend(&mut self, x: i64, y: i64, color: [f32; 4]) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let alpha = color[3].clamp(0.0, 1.0);
+        for (pixel, &channel) in self.pixels[i..i + 3].iter_mut().zip(&color[..3]) {
+            let below = *pixel as f32 / 255.0;
+            *pixel = to_byte(channel * alpha + below * (1.0 - alpha));
+        }
+        let below = self.pixels[i + 3] as f32 / 255.0;
+        self.pixels[i + 3] = to_byte(alpha + below * (1.0 - alpha));
+    }
+
+    /// blends every pixel whose centre is inside the window rectangle [x0, y0, x1, y1]
+    fn fill(&mut self, rect: [f64; 4], color: [f32; 4]) {
+        let (x0, y0) = ((rect[0] - 0.5).ceil().max(0.0) as i64, (rect[1] - 0.5).ceil().max(0.0) as i64);
+        let x1 = ((rect[2] - 0.5).ceil() as i64).min(self.width as i64);
+        let y1 = ((rect[3] - 0.5).ceil() as i64).min(self.height as i64);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.blend(x, y, color);
+            }
+        }
+    }
+}
+
+impl SpriteImages {
+    /// reads the sprites from the assets folder, found the same way the window finds it
+    pub fn load() -> Result<Self, String> {
+        let assets = find_folder::Search::ParentsThenKids(3, 3)
+            .for_folder("assets")
+            .map_err(|e| format!("could not find assets folder: {:?}", e))?;
+        SpriteImages::load_from(&assets)
+    }
+
+    pub fn load_from(assets: &Path) -> Result<Self, String> {
+        Ok(SpriteImages {
+            player: Image::load(&assets.join("player.png"))?,
+            player_move: Image::load(&assets.join("player_move.png"))?,
+            heart: Image::load(&assets.join("heart.png"))?,
+            enemy: Image::load(&assets.join("enemy.png"))?,
+        })
+    }
+
+    fn get(&self, sprite: Sprite) -> &Image {
+        match sprite {
+            Sprite::Player => &self.player,
+            Sprite::PlayerMove => &self.player_move,
+            Sprite::Heart => &self.heart,
+            Sprite::Enemy => &self.enemy,
+        }
+    }
+}
+
+impl<'a> SoftwareRenderer<'a> {
+    /// a renderer drawing into a new transparent image of the given size
+    pub fn new(width: u32, height: u32, sprites: &'a SpriteImages) -> Self {
+        SoftwareRenderer {
+            image: Image::new(width, height),
+            sprites,
+            offset: [0.0, 0.0],
+            scale: 1.0,
+        }
+    }
+
+    /// what has been drawn so far
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    pub fn into_image(self) -> Image {
+        self.image
+    }
+
+    /// a point in logical units moved into the image
+    fn to_window(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.offset[0] + x * self.scale, self.offset[1] + y * self.scale)
+    }
+}
+
+impl<'a> Renderer for SoftwareRenderer<'a> {
+    fn clear(&mut self, color: [f32; 4]) {
+        let pixel = [to_byte(color[0]), to_byte(color[1]), to_byte(color[2]), to_byte(color[3])];
+        for chunk in self.image.pixels.chunks_mut(4) {
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+
+    fn set_view(&mut self, offset: [f64; 2], scale: f64) {
+        self.offset = offset;
+        self.scale = scale;
+    }
+
+    fn rectangle(&mut self, color: [f32; 4], rect: [f64; 4]) {
+        let (x0, y0) = self.to_window(rect[0], rect[1]);
+        let (x1, y1) = self.to_window(rect[0] + rect[2], rect[1] + rect[3]);
+        self.image.fill([x0, y0, x1, y1], color);
+    }
+
+    /// each pixel takes the nearest pixel of the sprite, scaled by the view
+    fn sprite(&mut self, sprite: Sprite, color: [f32; 4], x: f64, y: f64) {
+        let source = self.sprites.get(sprite);
+        let (x0, y0) = self.to_window(x, y);
+        let (x1, y1) = self.to_window(x + source.width as f64, y + source.height as f64);
+        for py in (y0 - 0.5).ceil().max(0.0) as i64..((y1 - 0.5).ceil() as i64).min(self.image.height as i64) {
+            for px in (x0 - 0.5).ceil().max(0.0) as i64..((x1 - 0.5).ceil() as i64).min(self.image.width as i64) {
+                let sx = (((px as f64 + 0.5 - x0) / self.scale) as u32).min(source.width - 1);
+                let sy = (((py as f64 + 0.5 - y0) / self.scale) as u32).min(source.height - 1);
+                let texel = source.pixel(sx, sy);
+                let tinted = [
+                    texel[0] as f32 / 255.0 * color[0],
+                    texel[1] as f32 / 255.0 * color[1],
+                    texel[2] as f32 / 255.0 * color[2],
+                    texel[3] as f32 / 255.0 * color[3],
+                ];
+                self.image.blend(px, py, tinted);
+            }
+        }
+    }
+
+    /// written in the built in block font rather than the game font, which would need a font renderer
+    fn text(&mut self, color: [f32; 4], size: u32, text: &str, x: f64, y: f64) {
+        let column = size as f64 / TEXT_COLUMN;
+        let row = size as f64 / TEXT_ROW;
+        let top = y - row * GLYPH_HEIGHT as f64;
+        for (i, c) in text.chars().enumerate() {
+            let glyph = font::glyph(c);
+            let left = x + column * (ADVANCE as usize * i) as f64;
+            for gy in 0..GLYPH_HEIGHT {
+                for gx in 0..GLYPH_WIDTH {
+                    if font::is_set(&glyph, gx, gy) {
+                        let (x0, y0) = self.to_window(left + column * gx as f64, top + row * gy as f64);
+                        let (x1, y1) = self.to_window(left + column * (gx + 1) as f64, top + row * (gy + 1) as f64);
+                        self.image.fill([x0, y0, x1, y1], color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// every pixel whose centre is within half the width of the line
+    fn line(&mut self, color: [f32; 4], width: f64, line: [f64; 4]) {
+        let (ax, ay) = self.to_window(line[0], line[1]);
+        let (bx, by) = self.to_window(line[2], line[3]);
+        let half = (width * self.scale / 2.0).max(0.5);
+        let (dx, dy) = (bx - ax, by - ay);
+        let length = dx * dx + dy * dy;
+        let y0 = (ay.min(by) - half).floor().max(0.0) as i64;
+        let y1 = ((ay.max(by) + half).ceil() as i64).min(self.image.height as i64);
+        let x0 = (ax.min(bx) - half).floor().max(0.0) as i64;
+        let x1 = ((ax.max(bx) + half).ceil() as i64).min(self.image.width as i64);
+        for py in y0..y1 {
+            for px in x0..x1 {
+                let (cx, cy) = (px as f64 + 0.5, py as f64 + 0.5);
+                // how far along the line the nearest point is, from 0 at the start to 1 at the end
+                let t = if length > 0.0 { (((cx - ax) * dx + (cy - ay) * dy) / length).clamp(0.0, 1.0) } else { 0.0 };
+                let (nx, ny) = (ax + dx * t - cx, ay + dy * t - cy);
+                if nx * nx + ny * ny <= half * half {
+                    self.image.blend(px, py, color);
+                }
+            }
+        }
+    }
+}
+
+/// draws the game's current screen into a new image of the given size
+pub fn render(game: &mut Game, sprites: &SpriteImages, width: u32, height: u32) -> Image {
+    let mut renderer = SoftwareRenderer::new(width, height, sprites);
+    game.draw(&mut renderer, [width as f64, height as f64]);
+    renderer.into_image()
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use campaign::Campaign;
+    use game::{Mode, LOGICAL_SIZE};
+    use highscores::HighScores;
+    use settings::Settings;
+    use std::path::PathBuf;
+
+    fn sprites() -> SpriteImages {
+        SpriteImages::load_from(&PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/assets"))).unwrap()
+    }
+
+    #[test]
+    fn test_rectangle_and_view() {
+        let sprites = sprites();
+        let mut renderer = SoftwareRenderer::new(20, 10, &sprites);
+        renderer.clear([0.0, 0.0, 0.0, 1.0]);
+        renderer.set_view([2.0, 0.0], 2.0);
+        renderer.rectangle([1.0, 0.0, 0.0, 1.0], [1.0, 1.0, 2.0, 1.0]);
+        let image = renderer.image();
+        assert!(image.pixel(4, 2) == [255, 0, 0, 255] && image.pixel(7, 3) == [255, 0, 0, 255]);
+        assert!(image.pixel(3, 2) == [0, 0, 0, 255] && image.pixel(8, 2) == [0, 0, 0, 255]);
+        assert!(image.pixel(4, 4) == [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_blending() {
+        let mut image = Image::new(1, 1);
+        image.blend(0, 0, [1.0, 1.0, 1.0, 1.0]);
+        image.blend(0, 0, [0.0, 0.0, 0.0, 0.5]);
+        assert!(image.pixel(0, 0) == [128, 128, 128, 255]);
+        image.blend(5, 5, [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_sprite_tint_and_text() {
+        let sprites = sprites();
+        let mut renderer = SoftwareRenderer::new(100, 100, &sprites);
+        renderer.sprite(Sprite::Enemy, [0.0, 1.0, 0.0, 1.0], 10.0, 10.0);
+        renderer.text([1.0, 1.0, 1.0, 1.0], 20, "I", 60.0, 80.0);
+        renderer.line([0.0, 0.0, 1.0, 1.0], 2.0, [0.0, 95.0, 100.0, 95.0]);
+        let image = renderer.into_image();
+        let drawn: Vec<[u8; 4]> = (10..56).flat_map(|y| (10..56).map(move |x| (x, y)))
+            .map(|(x, y)| image.pixel(x, y))
+            .filter(|pixel| pixel[3] > 0)
+            .collect();
+        assert!(!drawn.is_empty() && drawn.iter().all(|pixel| pixel[0] == 0 && pixel[2] == 0));
+        // the middle of the I stands between the baseline and 0.7 of the font size above it
+        assert!(image.pixel(63, 70) == [255, 255, 255, 255] && image.pixel(63, 85) == [0, 0, 0, 0]);
+        assert!(image.pixel(50, 95) == [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_render_game_to_png() {
+        let sprites = sprites();
+        let mut game = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        game.start_seeded(Mode::Campaign, 3);
+        let image = render(&mut game, &sprites, LOGICAL_SIZE[0] as u32, LOGICAL_SIZE[1] as u32);
+        let wall = game.walls[0];
+        let color = game.settings().video.wall_color;
+        let expected = [to_byte(color[0]), to_byte(color[1]), to_byte(color[2]), 255];
+        assert!(image.pixel(wall[0] as u32 + 5, wall[1] as u32 + 5) == expected);
+        let decoded = Image::from_png(&image.to_png().unwrap()).unwrap();
+        assert!(decoded == image);
+        assert!(Image::from_png(b"GIF89a").is_err());
+    }
+}
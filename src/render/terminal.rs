@@ -0,0 +1,264 @@
+//! draws the game as coloured characters for playing in a terminal
+//! the window is measured in cells, a cell is one unit across and two down so the room keeps its shape
+
+use std::fmt::Write;
+
+use render::{Renderer, Sprite};
+
+/// terminal cells are about twice as tall as they are wide
+pub const CELL_HEIGHT: f64 = 2.0;
+
+/// a character with its colours from the 256 colour palette
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: u8,
+    pub bg: u8,
+}
+
+/// the character each sprite is drawn as, its size in pixels and the main colour of its image
+fn sprite_look(sprite: Sprite) -> (char, [f64; 2], [f32; 3]) {
+    match sprite {
+        Sprite::Player => ('@', [35.0, 53.0], [1.0, 0.56, 0.42]),
+        Sprite::PlayerMove => ('@', [45.0, 56.0], [0.97, 0.67, 0.4]),
+        Sprite::Heart => ('♥', [30.0, 28.0], [0.76, 0.03, 0.03]),
+        Sprite::Enemy => ('R', [46.0, 46.0], [0.98, 0.45, 0.1]),
+    }
+}
+
+/// the nearest colour in the 6x6x6 cube of the 256 colour palette
+pub fn color_index(color: [f32; 4]) -> u8 {
+    let level = |channel: f32| (channel.clamp(0.0, 1.0) * 5.0).round() as u8;
+    16 + 36 * level(color[0]) + 6 * level(color[1]) + level(color[2])
+}
+
+/// the cells from start to end that a span covers, the cells whose middles are inside it
+/// or the one cell under its middle when it is too thin to cover any middle
+fn span(start: f64, end: f64) -> (i64, i64) {
+    let (first, last) = ((start - 0.5).ceil() as i64, (end - 0.5).ceil() as i64);
+    if last > first {
+        (first, last)
+    } else {
+        let middle = ((start + end) / 2.0).floor() as i64;
+        (middle, middle + 1)
+    }
+}
+
+/// a grid of cells the game is drawn into and the cells last sent to the terminal,
+/// so each frame only sends what changed
+pub struct TerminalRenderer {
+    cols: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+    shown: Vec<Option<Cell>>,
+    offset: [f64; 2],
+    scale: f64,
+}
+
+impl TerminalRenderer {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let blank = Cell { ch: ' ', fg: 15, bg: 16 };
+        TerminalRenderer {
+            cols,
+            rows,
+            cells: vec![blank; cols * rows],
+            shown: vec![None; cols * rows],
+            offset: [0.0, 0.0],
+            scale: 1.0,
+        }
+    }
+
+    /// columns and rows
+    pub fn size(&self) -> (usize, usize) {
+        (self.cols, self.rows)
+    }
+
+    pub fn cell(&self, col: usize, row: usize) -> Cell {
+        self.cells[row * self.cols + col]
+    }
+
+    /// changes the cell at col, row if it is on the grid
+    fn put<F: Fn(&mut Cell)>(&mut self, col: i64, row: i64, change: F) {
+        if col >= 0 && row >= 0 && (col as usize) < self.cols && (row as usize) < self.rows {
+            change(&mut self.cells[row as usize * self.cols + col as usize]);
+        }
+    }
+
+    /// writes text straight onto the grid starting at col, row, the view does not apply
+    pub fn write(&mut self, col: usize, row: usize, text: &str, color: [f32; 4]) {
+        let fg = color_index(color);
+        for (i, ch) in text.chars().enumerate() {
+            self.put((col + i) as i64, row as i64, |cell| {
+                cell.ch = ch;
+                cell.fg = fg;
+            });
+        }
+    }
+
+    /// the cell a point of the view is in
+    fn to_cell(&self, x: f64, y: f64) -> (i64, i64) {
+        let (x, y) = (self.offset[0] + x * self.scale, self.offset[1] + y * self.scale);
+        (x.floor() as i64, (y / CELL_HEIGHT).floor() as i64)
+    }
+
+    /// the escape codes that bring the terminal up to date with the grid
+    pub fn frame(&mut self) -> String {
+        let mut out = String::new();
+        let mut cursor = None;
+        let mut colors = None;
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let i = row * self.cols + col;
+                let cell = self.cells[i];
+                if self.shown[i] == Some(cell) {
+                    continue
+                }
+                if cursor != Some((col, row)) {
+                    let _ = write!(out, "\x1b[{};{}H", row + 1, col + 1);
+                }
+                if colors != Some((cell.fg, cell.bg)) {
+                    let _ = write!(out, "\x1b[38;5;{};48;5;{}m", cell.fg, cell.bg);
+                    colors = Some((cell.fg, cell.bg));
+                }
+                out.push(cell.ch);
+                cursor = Some((col + 1, row));
+                self.shown[i] = Some(cell);
+            }
+        }
+        if !out.is_empty() {
+            out.push_str("\x1b[0m");
+        }
+        out
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn clear(&mut self, color: [f32; 4]) {
+        let blank = Cell { ch: ' ', fg: 15, bg: color_index(color) };
+        for cell in &mut self.cells {
+            *cell = blank;
+        }
+    }
+
+    fn set_view(&mut self, offset: [f64; 2], scale: f64) {
+        self.offset = offset;
+        self.scale = scale;
+    }
+
+    /// fills the cells it covers, one too small to fill a cell is drawn as a dot like a bullet
+    fn rectangle(&mut self, color: [f32; 4], rect: [f64; 4]) {
+        if color[3] <= 0.0 {
+            return
+        }
+        let (x0, y0) = (self.offset[0] + rect[0] * self.scale, self.offset[1] + rect[1] * self.scale);
+        let (x1, y1) = (x0 + rect[2] * self.scale, y0 + rect[3] * self.scale);
+        let index = color_index(color);
+        if x1 - x0 < 1.0 && y1 - y0 < CELL_HEIGHT {
+            let (col, row) = (((x0 + x1) / 2.0).floor() as i64, ((y0 + y1) / 2.0 / CELL_HEIGHT).floor() as i64);
+            self.put(col, row, |cell| {
+                cell.ch = '•';
+                cell.fg = index;
+            });
+            return
+        }
+        let (first_col, last_col) = span(x0, x1);
+        let (first_row, last_row) = span(y0 / CELL_HEIGHT, y1 / CELL_HEIGHT);
+        for row in first_row..last_row {
+            for col in first_col..last_col {
+                self.put(col, row, |cell| {
+                    cell.ch = ' ';
+                    cell.bg = index;
+                });
+            }
+        }
+    }
+
+    /// one character in the middle of where the image would be
+    fn sprite(&mut self, sprite: Sprite, color: [f32; 4], x: f64, y: f64) {
+        let (ch, size, base) = sprite_look(sprite);
+        let (col, row) = self.to_cell(x + size[0] / 2.0, y + size[1] / 2.0);
+        let fg = color_index([base[0] * color[0], base[1] * color[1], base[2] * color[2], color[3]]);
+        self.put(col, row, |cell| {
+            cell.ch = ch;
+            cell.fg = fg;
+        });
+    }
+
+    /// one character a cell on the row through the middle of the capitals
+    fn text(&mut self, color: [f32; 4], size: u32, text: &str, x: f64, y: f64) {
+        let (col, row) = self.to_cell(x, y - size as f64 * 0.35);
+        let fg = color_index(color);
+        for (i, ch) in text.chars().enumerate() {
+            self.put(col + i as i64, row, |cell| {
+                cell.ch = ch;
+                cell.fg = fg;
+            });
+        }
+    }
+
+    /// dots every half cell along the line
+    fn line(&mut self, color: [f32; 4], _width: f64, line: [f64; 4]) {
+        let (dx, dy) = (line[2] - line[0], line[3] - line[1]);
+        let steps = ((dx * dx + dy * dy).sqrt() * self.scale * 2.0).ceil().max(1.0) as usize;
+        let fg = color_index(color);
+        for step in 0..steps + 1 {
+            let t = step as f64 / steps as f64;
+            let (col, row) = self.to_cell(line[0] + dx * t, line[1] + dy * t);
+            self.put(col, row, |cell| {
+                cell.ch = '·';
+                cell.fg = fg;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use campaign::Campaign;
+    use game::{Game, Mode, BLACK};
+    use highscores::HighScores;
+    use settings::Settings;
+
+    #[test]
+    fn test_color_index() {
+        assert!(color_index(BLACK) == 16);
+        assert!(color_index([1.0, 1.0, 1.0, 1.0]) == 231);
+        assert!(color_index([1.0, 0.0, 0.0, 1.0]) == 196);
+    }
+
+    #[test]
+    fn test_rectangles_fill_cells_or_dot() {
+        let mut renderer = TerminalRenderer::new(10, 5);
+        renderer.rectangle([1.0, 0.0, 0.0, 1.0], [1.0, 2.0, 3.0, 4.0]);
+        assert!(renderer.cell(1, 1).bg == 196 && renderer.cell(3, 2).bg == 196);
+        assert!(renderer.cell(4, 1).bg == 16 && renderer.cell(1, 3).bg == 16);
+        renderer.rectangle([1.0, 1.0, 1.0, 1.0], [7.2, 6.2, 0.5, 0.5]);
+        assert!(renderer.cell(7, 3) == Cell { ch: '•', fg: 231, bg: 16 });
+    }
+
+    #[test]
+    fn test_frame_sends_only_changes() {
+        let mut renderer = TerminalRenderer::new(4, 2);
+        assert!(renderer.frame().matches(' ').count() == 8);
+        assert!(renderer.frame().is_empty());
+        renderer.write(2, 1, "A", [1.0, 1.0, 1.0, 1.0]);
+        assert!(renderer.frame() == "\x1b[2;3H\x1b[38;5;231;48;5;16mA\x1b[0m");
+    }
+
+    #[test]
+    fn test_game_in_cells() {
+        let mut game = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+        game.start_seeded(Mode::Campaign, 3);
+        let mut renderer = TerminalRenderer::new(90, 30);
+        game.draw(&mut renderer, [90.0, 60.0]);
+        let cells: Vec<Cell> = (0..30).flat_map(|row| (0..90).map(move |col| (col, row)))
+            .map(|(col, row)| renderer.cell(col, row))
+            .collect();
+        let wall = color_index(game.settings().video.wall_color);
+        assert!(cells.iter().filter(|cell| cell.ch == 'R').count() == 6);
+        assert!(cells.iter().filter(|cell| cell.ch == '♥').count() == 3);
+        assert!(cells.iter().any(|cell| cell.ch == '@'));
+        assert!(renderer.cell(1, 5).bg == wall);
+    }
+}
@@ -0,0 +1,153 @@
+//! a game saved as its seed and the action of every update, so it can be played again exactly
+//! the file is one JSON object like `{"seed": 42, "mode": "campaign", "difficulty": "normal", "actions": [0, 7, 7, 2]}`
+//! where each action is its number from `Action::from_index`, with `"campaign": FILE` added for a campaign of its own
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use campaign::Campaign;
+use difficulty::Difficulty;
+use environment::{Action, Environment, ACTION_COUNT};
+use game::{Mode, FPS, LOGICAL_SIZE, RECORD_DELAY, RECORD_RATE};
+use net::{mode_from_name, mode_name};
+use render::gif::GifWriter;
+use render::software::SpriteImages;
+use serde_json::{self, Value};
+use settings::Settings;
+
+/// how a one player game was played from start to end
+/// campaign: the campaign file it was played in, none for the classic one
+/// actions: the first player's action for each update after the game started
+#[derive(Clone, PartialEq, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub mode: Mode,
+    pub difficulty: Difficulty,
+    pub campaign: Option<String>,
+    pub actions: Vec<Action>,
+}
+
+impl Replay {
+    pub fn parse(text: &str) -> Result<Replay, String> {
+        let value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let seed = value["seed"].as_u64().ok_or("seed must be a whole number")?;
+        let mode = match value["mode"].as_str().and_then(mode_from_name) {
+            Some(Mode::Versus) | None => return Err("mode must be campaign or endless".to_string()),
+            Some(mode) => mode,
+        };
+        let difficulty = value["difficulty"].as_str().and_then(Difficulty::from_name)
+            .ok_or("difficulty must be easy, normal or hard")?;
+        let campaign = match value.get("campaign") {
+            None | Some(&Value::Null) => None,
+            Some(path) => Some(path.as_str().ok_or("campaign must be a file name")?.to_string()),
+        };
+        let actions = value["actions"].as_array().ok_or("actions must be a list")?.iter()
+            .map(|action| match action.as_u64() {
+                Some(n) if n < ACTION_COUNT as u64 => Ok(Action::from_index(n as usize)),
+                _ => Err(format!("each action must be a number from 0 to {}", ACTION_COUNT - 1)),
+            })
+            .collect::<Result<Vec<Action>, String>>()?;
+        Ok(Replay { seed, mode, difficulty, campaign, actions })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let actions: Vec<usize> = self.actions.iter().map(Action::index).collect();
+        let mut value = json!({
+            "seed": self.seed,
+            "mode": mode_name(self.mode),
+            "difficulty": self.difficulty.name(),
+            "actions": actions,
+        });
+        if let Some(ref campaign) = self.campaign {
+            value["campaign"] = json!(campaign);
+        }
+        value
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Replay::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_json().to_string()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// the campaign the replay was played in
+    pub fn load_campaign(&self) -> Result<Campaign, String> {
+        match self.campaign {
+            Some(ref path) => Campaign::load(Path::new(path)),
+            None => Ok(Campaign::default()),
+        }
+    }
+
+    /// plays the replay again without a window and writes it to out as a GIF at the game's own size,
+    /// with the same frame rate as a recording made in the game
+    /// the GIF ends with the last action, or sooner if the game is over first
+    pub fn render_gif<W: Write>(&self, campaign: Campaign, sprites: &SpriteImages, out: W) -> io::Result<()> {
+        let mut settings = Settings::default();
+        settings.gameplay.difficulty = self.difficulty;
+        let mut env = Environment::new(settings, campaign, self.mode);
+        env.reset(self.seed);
+        let (width, height) = (LOGICAL_SIZE[0] as u32, LOGICAL_SIZE[1] as u32);
+        let mut gif = GifWriter::new(out, width, height)?;
+        let steps_per_frame = (FPS as f64 / RECORD_RATE).round().max(1.0) as usize;
+        gif.frame(&env.render(sprites, width, height), RECORD_DELAY)?;
+        for (step, &action) in self.actions.iter().enumerate() {
+            let (_, _, done) = env.step(action);
+            if done || (step + 1) % steps_per_frame == 0 {
+                gif.frame(&env.render(sprites, width, height), RECORD_DELAY)?;
+            }
+            if done {
+                break
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use game::Direction;
+    use std::path::PathBuf;
+
+    fn replay() -> Replay {
+        Replay {
+            seed: 5,
+            mode: Mode::Endless,
+            difficulty: Difficulty::Hard,
+            campaign: None,
+            actions: vec![Action::default(), Action { dir: Some(Direction::EAST), fire: true }, Action::from_index(4)],
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let line = r#"{"seed": 5, "mode": "endless", "difficulty": "hard", "actions": [0, 8, 4]}"#;
+        assert!(Replay::parse(line) == Ok(replay()));
+        assert!(Replay::parse(&replay().to_json().to_string()) == Ok(replay()));
+        let mut other = replay();
+        other.campaign = Some("rooms.txt".to_string());
+        assert!(Replay::parse(&other.to_json().to_string()) == Ok(other));
+        assert!(Replay::parse(r#"{"seed": 5, "mode": "versus", "difficulty": "hard", "actions": []}"#).is_err());
+        assert!(Replay::parse(r#"{"seed": 5, "mode": "endless", "difficulty": "hard", "actions": [10]}"#).is_err());
+        assert!(Replay::parse(r#"{"mode": "endless", "difficulty": "hard", "actions": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_render_gif_plays_the_same_game() {
+        let sprites = SpriteImages::load_from(&PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/assets"))).unwrap();
+        let mut replay = replay();
+        replay.actions = (0..6).map(|i| Action::from_index(i % ACTION_COUNT)).collect();
+        let render = || {
+            let mut bytes = Vec::new();
+            replay.render_gif(Campaign::default(), &sprites, &mut bytes).unwrap();
+            bytes
+        };
+        let bytes = render();
+        assert!(&bytes[..6] == b"GIF89a" && bytes[bytes.len() - 1] == 0x3b);
+        assert!(bytes == render());
+    }
+}
@@ -0,0 +1,296 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use config;
+use controls::Bindings;
+use difficulty::Difficulty;
+
+/// the file in the config directory that holds the settings
+pub const SETTINGS_FILE: &str = "settings.cfg";
+
+/// colours that can be picked from the options menu
+pub const PALETTE: [(&str, [f32; 4]); 6] = [
+    ("BLUE", [0.5, 0.6, 0.7, 1.0]),
+    ("YELLOW", [1.0, 1.0, 0.5, 1.0]),
+    ("GREEN", [0.4, 0.8, 0.4, 1.0]),
+    ("RED", [0.9, 0.3, 0.3, 1.0]),
+    ("WHITE", [1.0, 1.0, 1.0, 1.0]),
+    ("ORANGE", [1.0, 0.6, 0.2, 1.0]),
+];
+
+/// window sizes that can be picked from the options menu
+pub const WINDOW_SIZES: [[u32; 2]; 3] = [[900, 600], [1200, 800], [1350, 900]];
+/// the smallest window remembered, a smaller one leaves no room to play in
+pub const MIN_WINDOW_SIZE: [u32; 2] = [300, 200];
+
+/// window size and colours
+/// width & height: size of the window when it is not fullscreen
+/// fullscreen: whether the game covers the whole screen
+/// wall_color: colour of the maze walls
+/// text_color: colour of the score, level and messages
+#[derive(Clone, PartialEq, Debug)]
+pub struct VideoSettings {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub wall_color: [f32; 4],
+    pub text_color: [f32; 4],
+}
+
+/// sound_on: whether any sound is played
+/// volume: from 0.0 (silent) to 1.0
+#[derive(Clone, PartialEq, Debug)]
+pub struct AudioSettings {
+    pub sound_on: bool,
+    pub volume: f32,
+}
+
+/// difficulty: how hard the robots are
+/// room_clear_bonus: points for leaving a room with every robot destroyed
+/// extra_life_scores: scores that each give an extra life when reached
+/// campaign: path to the campaign file START plays, empty for the built in four rooms
+/// friendly_fire: whether players' bullets hurt each other in a two player game
+/// versus_kills: kills that win a versus match, 0 for no limit
+/// versus_time: seconds in a versus round, 0 for no limit
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameplaySettings {
+    pub difficulty: Difficulty,
+    pub room_clear_bonus: u32,
+    pub extra_life_scores: Vec<u32>,
+    pub campaign: String,
+    pub friendly_fire: bool,
+    pub versus_kills: u32,
+    pub versus_time: f64,
+}
+
+/// everything the player can change, saved between runs
+/// controls is the first player's keys and controls_p2 the second player's
+/// the file has a [video], [audio], [controls], [controls_p2] and [gameplay] section
+/// with one `name = value` per line, # starts a comment
+#[derive(Clone, PartialEq, Debug)]
+pub struct Settings {
+    pub video: VideoSettings,
+    pub audio: AudioSettings,
+    pub controls: Bindings,
+    pub controls_p2: Bindings,
+    pub gameplay: GameplaySettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            video: VideoSettings {
+                width: 900,
+                height: 600,
+                fullscreen: false,
+                wall_color: PALETTE[0].1,
+                text_color: PALETTE[1].1,
+            },
+            audio: AudioSettings {
+                sound_on: true,
+                volume: 0.25,
+            },
+            controls: Bindings::default(),
+            controls_p2: Bindings::second_player(),
+            gameplay: GameplaySettings {
+                difficulty: Difficulty::Normal,
+                room_clear_bonus: 200,
+                extra_life_scores: vec![1000, 3000, 6000],
+                campaign: String::new(),
+                friendly_fire: false,
+                versus_kills: 5,
+                versus_time: 0.0,
+            },
+        }
+    }
+}
+
+/// reads `r, g, b, a` with each part between 0.0 and 1.0
+fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let parts: Vec<f32> = value.split(',').filter_map(|part| part.trim().parse().ok()).collect();
+    if parts.len() == 4 && parts.iter().all(|part| *part >= 0.0 && *part <= 1.0) {
+        Some([parts[0], parts[1], parts[2], parts[3]])
+    } else {
+        None
+    }
+}
+
+fn color_string(color: [f32; 4]) -> String {
+    format!("{}, {}, {}, {}", color[0], color[1], color[2], color[3])
+}
+
+/// the palette name of a colour, or CUSTOM if it was typed into the file
+pub fn color_name(color: [f32; 4]) -> &'static str {
+    PALETTE.iter().find(|&&(_, c)| c == color).map(|&(name, _)| name).unwrap_or("CUSTOM")
+}
+
+/// the palette colour after this one, custom colours go to the start of the palette
+pub fn next_color(color: [f32; 4]) -> [f32; 4] {
+    match PALETTE.iter().position(|&(_, c)| c == color) {
+        Some(i) => PALETTE[(i + 1) % PALETTE.len()].1,
+        None => PALETTE[0].1,
+    }
+}
+
+impl Settings {
+    /// the keys of a player, 0 for the first player and 1 for the second
+    pub fn bindings(&self, player: usize) -> &Bindings {
+        if player == 0 { &self.controls } else { &self.controls_p2 }
+    }
+
+    pub fn bindings_mut(&mut self, player: usize) -> &mut Bindings {
+        if player == 0 { &mut self.controls } else { &mut self.controls_p2 }
+    }
+
+    /// reads settings from a file, using the defaults if it is missing
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(contents) => Settings::parse(&contents),
+            Err(_) => Settings::default(),
+        }
+    }
+
+    /// writes the settings without ever leaving a half written file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        config::write_atomic(path, &self.to_file_string())
+    }
+
+    /// reads the settings file format
+    /// unknown names and values that do not parse keep their defaults
+    pub fn parse(contents: &str) -> Self {
+        let mut settings = Settings::default();
+        let mut section = String::new();
+        let mut controls = String::new();
+        let mut controls_p2 = String::new();
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line[1..line.len() - 1].trim().to_string();
+                continue
+            }
+            if section == "controls" || section == "controls_p2" {
+                let lines = if section == "controls" { &mut controls } else { &mut controls_p2 };
+                lines.push_str(line);
+                lines.push('\n');
+                continue
+            }
+            let mut parts = line.splitn(2, '=');
+            let (name, value) = match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) => (name.trim(), value.trim()),
+                _ => continue,
+            };
+            match (section.as_str(), name) {
+                ("video", "width") => if let Ok(v) = value.parse() {
+                    if v >= MIN_WINDOW_SIZE[0] { settings.video.width = v }
+                },
+                ("video", "height") => if let Ok(v) = value.parse() {
+                    if v >= MIN_WINDOW_SIZE[1] { settings.video.height = v }
+                },
+                ("video", "fullscreen") => if let Ok(v) = value.parse() { settings.video.fullscreen = v },
+                ("video", "wall_color") => if let Some(v) = parse_color(value) { settings.video.wall_color = v },
+                ("video", "text_color") => if let Some(v) = parse_color(value) { settings.video.text_color = v },
+                ("audio", "sound_on") => if let Ok(v) = value.parse() { settings.audio.sound_on = v },
+                ("audio", "volume") => if let Some(v) = value.parse::<f32>().ok().filter(|v| !v.is_nan()) {
+                    settings.audio.volume = v.clamp(0.0, 1.0)
+                },
+                ("gameplay", "difficulty") => if let Some(v) = Difficulty::from_name(value) {
+                    settings.gameplay.difficulty = v
+                },
+                ("gameplay", "room_clear_bonus") => if let Ok(v) = value.parse() {
+                    settings.gameplay.room_clear_bonus = v
+                },
+                ("gameplay", "extra_life_scores") => {
+                    let mut scores: Vec<u32> = value.split(',').filter_map(|score| score.trim().parse().ok()).collect();
+                    scores.sort();
+                    settings.gameplay.extra_life_scores = scores;
+                },
+                ("gameplay", "campaign") => settings.gameplay.campaign = value.to_string(),
+                ("gameplay", "friendly_fire") => if let Ok(v) = value.parse() {
+                    settings.gameplay.friendly_fire = v
+                },
+                ("gameplay", "versus_kills") => if let Ok(v) = value.parse() {
+                    settings.gameplay.versus_kills = v
+                },
+                ("gameplay", "versus_time") => if let Ok(v) = value.parse::<f64>() {
+                    settings.gameplay.versus_time = v.max(0.0)
+                },
+                _ => (),
+            }
+        }
+        settings.controls = Bindings::parse(&controls);
+        settings.controls_p2 = Bindings::parse_with_defaults(&controls_p2, Bindings::second_player());
+        settings
+    }
+
+    /// the settings in the file format
+    pub fn to_file_string(&self) -> String {
+        let mut contents = String::from("# berzerk settings\n");
+        contents.push_str("\n[video]\n");
+        contents.push_str(&format!("width = {}\n", self.video.width));
+        contents.push_str(&format!("height = {}\n", self.video.height));
+        contents.push_str(&format!("fullscreen = {}\n", self.video.fullscreen));
+        contents.push_str(&format!("wall_color = {}\n", color_string(self.video.wall_color)));
+        contents.push_str(&format!("text_color = {}\n", color_string(self.video.text_color)));
+        contents.push_str("\n[audio]\n");
+        contents.push_str(&format!("sound_on = {}\n", self.audio.sound_on));
+        contents.push_str(&format!("volume = {}\n", self.audio.volume));
+        contents.push_str("\n[controls]\n");
+        contents.push_str(&self.controls.to_file_string());
+        contents.push_str("\n[controls_p2]\n");
+        contents.push_str(&self.controls_p2.to_file_string());
+        contents.push_str("\n[gameplay]\n");
+        contents.push_str(&format!("difficulty = {}\n", self.gameplay.difficulty.name()));
+        contents.push_str(&format!("room_clear_bonus = {}\n", self.gameplay.room_clear_bonus));
+        let scores: Vec<String> = self.gameplay.extra_life_scores.iter().map(|score| score.to_string()).collect();
+        contents.push_str(&format!("extra_life_scores = {}\n", scores.join(", ")));
+        contents.push_str(&format!("campaign = {}\n", self.gameplay.campaign));
+        contents.push_str(&format!("friendly_fire = {}\n", self.gameplay.friendly_fire));
+        contents.push_str(&format!("versus_kills = {}\n", self.gameplay.versus_kills));
+        contents.push_str(&format!("versus_time = {}\n", self.gameplay.versus_time));
+        contents
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+    use controls::Action;
+    use piston::input::Key;
+
+    #[test]
+    fn test_round_trip() {
+        let mut settings = Settings::default();
+        settings.video.width = 1200;
+        settings.video.fullscreen = true;
+        settings.video.wall_color = PALETTE[3].1;
+        settings.audio.volume = 0.5;
+        settings.audio.sound_on = false;
+        settings.controls.bind(Action::Fire, Key::F);
+        settings.controls_p2.bind(Action::Fire, Key::G);
+        settings.gameplay.friendly_fire = true;
+        settings.gameplay.versus_kills = 0;
+        settings.gameplay.versus_time = 90.0;
+        settings.gameplay.difficulty = Difficulty::Hard;
+        settings.gameplay.room_clear_bonus = 0;
+        settings.gameplay.extra_life_scores = vec![500, 2000];
+        settings.gameplay.campaign = "assets/campaigns/gauntlet.cfg".to_string();
+        assert!(Settings::parse(&settings.to_file_string()) == settings);
+    }
+
+    #[test]
+    fn test_bad_values_keep_defaults() {
+        let settings = Settings::parse("[video]\nwidth = wide\nheight = 0\nwall_color = 2, 0, 0, 1\n[audio]\nvolume = 7\n");
+        assert!(settings.video.width == 900);
+        assert!(settings.video.height == 600);
+        assert!(settings.video.wall_color == PALETTE[0].1);
+        assert!(settings.audio.volume == 1.0);
+    }
+
+    #[test]
+    fn test_next_color() {
+        assert!(next_color(PALETTE[0].1) == PALETTE[1].1);
+        assert!(next_color(PALETTE[5].1) == PALETTE[0].1);
+        assert!(color_name([0.1, 0.1, 0.1, 1.0]) == "CUSTOM");
+    }
+}
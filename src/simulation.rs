@@ -0,0 +1,420 @@
+extern crate rand;
+
+use self::rand::{Rng, XorShiftRng};
+use bot::Bot;
+use campaign::Campaign;
+use difficulty::Difficulty;
+use environment::{Action, Environment, ACTION_COUNT};
+use game::{seeded_rng, Mode, State, FPS};
+use models::player::Cause;
+use net::mode_from_name;
+use replay::Replay;
+use serde_json::{Map, Value};
+use settings::Settings;
+
+/// game time in seconds that one step plays
+const STEP_TIME: f64 = 1.0 / FPS as f64;
+/// the causes in the order they are reported
+const CAUSES: [Cause; 4] = [Cause::RobotBullet, Cause::Robot, Cause::Wall, Cause::PlayerBullet];
+
+/// who plays the simulated games
+/// Scripted: the bot from the attract demo
+/// Random: a random action every step
+/// Idle: stands still and never shoots, to see what the robots do on their own
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Pilot {
+    Scripted,
+    Random,
+    Idle,
+}
+
+/// how a simulated game ended
+/// Unfinished: still going when the time ran out
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Outcome {
+    Won,
+    Lost,
+    Unfinished,
+}
+
+/// what to simulate, read from the command line
+/// first_seed: games are played with first_seed, first_seed + 1 and so on
+/// max_time: game time in seconds before a game is stopped as unfinished
+/// json: print the report as JSON instead of CSV
+/// replays: a folder to save a replay of every game in
+#[derive(Clone, PartialEq, Debug)]
+pub struct Options {
+    pub pilot: Pilot,
+    pub first_seed: u64,
+    pub games: u64,
+    pub difficulty: Difficulty,
+    pub mode: Mode,
+    pub campaign: Option<String>,
+    pub max_time: f64,
+    pub json: bool,
+    pub replays: Option<String>,
+}
+
+/// how one simulated game went
+/// room_times: seconds spent in each room entered, in order
+/// lives_lost: what took each life
+/// actions: what the pilot did each step, for saving a replay
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameRecord {
+    pub seed: u64,
+    pub outcome: Outcome,
+    pub score: u32,
+    pub room_times: Vec<f64>,
+    pub lives_lost: Vec<Cause>,
+    pub actions: Vec<Action>,
+}
+
+/// totals for one level over every game that reached it
+/// survived: games that went on to the next level, or won on this one
+/// time: seconds spent in the room, added up over the games
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct LevelStats {
+    pub reached: u32,
+    pub survived: u32,
+    pub time: f64,
+}
+
+/// the statistics of a batch of games
+/// levels: the first entry is level 1
+/// lives_lost & game_overs: counts in the order of CAUSES, game_overs only counts the life that ended a lost game
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Report {
+    pub games: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub unfinished: u32,
+    pub total_score: u64,
+    pub levels: Vec<LevelStats>,
+    pub lives_lost: [u32; 4],
+    pub game_overs: [u32; 4],
+}
+
+fn cause_name(cause: Cause) -> &'static str {
+    match cause {
+        Cause::RobotBullet => "robot_bullet",
+        Cause::Robot => "robot",
+        Cause::Wall => "wall",
+        Cause::PlayerBullet => "player_bullet",
+    }
+}
+
+fn cause_index(cause: Cause) -> usize {
+    CAUSES.iter().position(|&c| c == cause).unwrap_or(0)
+}
+
+/// a ratio that is zero rather than NaN when nothing was counted
+fn ratio(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 { part / whole } else { 0.0 }
+}
+
+impl Pilot {
+    pub fn from_name(name: &str) -> Option<Pilot> {
+        match name {
+            "scripted" => Some(Pilot::Scripted),
+            "random" => Some(Pilot::Random),
+            "idle" => Some(Pilot::Idle),
+            _ => None,
+        }
+    }
+}
+
+impl Options {
+    /// reads `--bot`, `--seed`, `--games`, `--difficulty`, `--mode`, `--campaign`, `--max-time` and `--format`,
+    /// every one is optional
+    pub fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or_else(|| format!("{} needs a value", arg))?;
+            match arg.as_str() {
+                "--bot" => options.pilot = Pilot::from_name(value)
+                    .ok_or("--bot must be scripted, random or idle")?,
+                "--seed" => options.first_seed = value.parse()
+                    .map_err(|_| "--seed must be a whole number")?,
+                "--games" => options.games = value.parse()
+                    .map_err(|_| "--games must be a whole number")?,
+                "--difficulty" => options.difficulty = Difficulty::from_name(value)
+                    .ok_or("--difficulty must be easy, normal or hard")?,
+                "--mode" => options.mode = match mode_from_name(value) {
+                    Some(Mode::Versus) | None => return Err("--mode must be campaign or endless".to_string()),
+                    Some(mode) => mode,
+                },
+                "--campaign" => options.campaign = Some(value.clone()),
+                "--replays" => options.replays = Some(value.clone()),
+                "--max-time" => options.max_time = match value.parse() {
+                    Ok(time) if time > 0.0 => time,
+                    _ => return Err("--max-time must be a number of seconds above zero".to_string()),
+                },
+                "--format" => options.json = match value.as_str() {
+                    "csv" => false,
+                    "json" => true,
+                    _ => return Err("--format must be csv or json".to_string()),
+                },
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            pilot: Pilot::Scripted,
+            first_seed: 1,
+            games: 1000,
+            difficulty: Difficulty::Normal,
+            mode: Mode::Campaign,
+            campaign: None,
+            max_time: 600.0,
+            json: false,
+            replays: None,
+        }
+    }
+}
+
+/// plays one game to the end, or until max_time seconds of game time have gone by
+/// the random pilot's moves come from the seed too so a record can always be played again
+pub fn play(env: &mut Environment, pilot: Pilot, seed: u64, max_time: f64) -> GameRecord {
+    let mut bot = Bot::new();
+    let mut rng: XorShiftRng = seeded_rng(!seed);
+    let mut observation = env.reset(seed);
+    let mut level = observation.level;
+    let mut room_times = Vec::new();
+    let mut room_steps = 0;
+    let mut actions = Vec::new();
+    let mut done = false;
+    let max_steps = (max_time / STEP_TIME) as u64;
+    for _ in 0..max_steps {
+        let action = match pilot {
+            Pilot::Scripted => bot.act(&observation),
+            Pilot::Random => Action::from_index(rng.gen_range(0, ACTION_COUNT)),
+            Pilot::Idle => Action::default(),
+        };
+        actions.push(action);
+        let (next, _, over) = env.step(action);
+        observation = next;
+        room_steps += 1;
+        if observation.level != level {
+            room_times.push(room_steps as f64 * STEP_TIME);
+            room_steps = 0;
+            level = observation.level;
+        }
+        if over {
+            done = true;
+            break
+        }
+    }
+    // a game won by leaving the last room has already counted it
+    if room_steps > 0 {
+        room_times.push(room_steps as f64 * STEP_TIME);
+    }
+    let outcome = match env.game().state() {
+        State::Won | State::EnterInitials { won: true } => Outcome::Won,
+        _ if done => Outcome::Lost,
+        _ => Outcome::Unfinished,
+    };
+    GameRecord {
+        seed,
+        outcome,
+        score: observation.score,
+        room_times,
+        lives_lost: env.game().lives_lost(),
+        actions,
+    }
+}
+
+/// plays every game the options ask for, report is called after each one so progress can be shown
+pub fn run<F: FnMut(&GameRecord)>(options: &Options, campaign: Campaign, mut report: F) {
+    let mut settings = Settings::default();
+    settings.gameplay.difficulty = options.difficulty;
+    let mut env = Environment::new(settings, campaign, options.mode);
+    for seed in options.first_seed..options.first_seed.saturating_add(options.games) {
+        report(&play(&mut env, options.pilot, seed, options.max_time));
+    }
+}
+
+impl GameRecord {
+    /// the game as a replay, options are the ones it was played with
+    pub fn replay(&self, options: &Options) -> Replay {
+        Replay {
+            seed: self.seed,
+            mode: options.mode,
+            difficulty: options.difficulty,
+            campaign: options.campaign.clone(),
+            actions: self.actions.clone(),
+        }
+    }
+}
+
+impl Report {
+    pub fn add(&mut self, record: &GameRecord) {
+        self.games += 1;
+        match record.outcome {
+            Outcome::Won => self.won += 1,
+            Outcome::Lost => self.lost += 1,
+            Outcome::Unfinished => self.unfinished += 1,
+        }
+        self.total_score += record.score as u64;
+        if self.levels.len() < record.room_times.len() {
+            self.levels.resize(record.room_times.len(), LevelStats::default());
+        }
+        let last = record.room_times.len().saturating_sub(1);
+        for (room, &time) in record.room_times.iter().enumerate() {
+            let stats = &mut self.levels[room];
+            stats.reached += 1;
+            stats.time += time;
+            if room < last || record.outcome == Outcome::Won {
+                stats.survived += 1;
+            }
+        }
+        for &cause in &record.lives_lost {
+            self.lives_lost[cause_index(cause)] += 1;
+        }
+        if record.outcome == Outcome::Lost {
+            if let Some(&cause) = record.lives_lost.last() {
+                self.game_overs[cause_index(cause)] += 1;
+            }
+        }
+    }
+
+    pub fn average_score(&self) -> f64 {
+        ratio(self.total_score as f64, self.games as f64)
+    }
+
+    /// one statistic per row as `stat,key,value`, the key is the level or the cause where there is one
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("stat,key,value\n");
+        csv.push_str(&format!("games,,{}\nwon,,{}\nlost,,{}\nunfinished,,{}\naverage_score,,{}\n",
+            self.games, self.won, self.lost, self.unfinished, self.average_score()));
+        for (i, stats) in self.levels.iter().enumerate() {
+            csv.push_str(&format!("reached,{},{}\n", i + 1, stats.reached));
+            csv.push_str(&format!("survival_rate,{},{}\n", i + 1, ratio(stats.survived as f64, stats.reached as f64)));
+            csv.push_str(&format!("room_time,{},{}\n", i + 1, ratio(stats.time, stats.reached as f64)));
+        }
+        for (i, &cause) in CAUSES.iter().enumerate() {
+            csv.push_str(&format!("lives_lost,{},{}\n", cause_name(cause), self.lives_lost[i]));
+        }
+        for (i, &cause) in CAUSES.iter().enumerate() {
+            csv.push_str(&format!("game_over,{},{}\n", cause_name(cause), self.game_overs[i]));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> Value {
+        let levels: Vec<Value> = self.levels.iter().enumerate()
+            .map(|(i, stats)| json!({
+                "level": i + 1,
+                "reached": stats.reached,
+                "survival_rate": ratio(stats.survived as f64, stats.reached as f64),
+                "room_time": ratio(stats.time, stats.reached as f64),
+            }))
+            .collect();
+        let causes = |counts: &[u32; 4]| CAUSES.iter().enumerate()
+            .map(|(i, &cause)| (cause_name(cause).to_string(), json!(counts[i])))
+            .collect::<Map<String, Value>>();
+        json!({
+            "games": self.games,
+            "won": self.won,
+            "lost": self.lost,
+            "unfinished": self.unfinished,
+            "average_score": self.average_score(),
+            "levels": levels,
+            "lives_lost": causes(&self.lives_lost),
+            "game_over": causes(&self.game_overs),
+        })
+    }
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert!(Options::parse(&[]) == Ok(Options::default()));
+        let options = Options::parse(&args("--bot random --seed 5 --games 20 --difficulty hard --mode endless --format json")).unwrap();
+        assert!(options.pilot == Pilot::Random && options.first_seed == 5 && options.games == 20);
+        assert!(options.difficulty == Difficulty::Hard && options.mode == Mode::Endless && options.json);
+        assert!(Options::parse(&args("--replays out")).unwrap().replays == Some("out".to_string()));
+        assert!(Options::parse(&args("--mode versus")).is_err());
+        assert!(Options::parse(&args("--games")).is_err());
+        assert!(Options::parse(&args("--max-time 0")).is_err());
+        assert!(Options::parse(&args("--speed 2")).is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = Report::default();
+        report.add(&GameRecord {
+            seed: 1,
+            outcome: Outcome::Won,
+            score: 300,
+            room_times: vec![10.0, 20.0],
+            lives_lost: vec![Cause::Wall],
+            actions: Vec::new(),
+        });
+        report.add(&GameRecord {
+            seed: 2,
+            outcome: Outcome::Lost,
+            score: 100,
+            room_times: vec![30.0],
+            lives_lost: vec![Cause::Robot, Cause::RobotBullet],
+            actions: Vec::new(),
+        });
+        assert!(report.games == 2 && report.won == 1 && report.lost == 1);
+        assert!(report.average_score() == 200.0);
+        assert!(report.levels[0] == LevelStats { reached: 2, survived: 1, time: 40.0 });
+        assert!(report.levels[1] == LevelStats { reached: 1, survived: 1, time: 20.0 });
+        assert!(report.lives_lost == [1, 1, 1, 0] && report.game_overs == [1, 0, 0, 0]);
+        let csv = report.to_csv();
+        assert!(csv.starts_with("stat,key,value\ngames,,2\n"));
+        assert!(csv.contains("survival_rate,1,0.5\nroom_time,1,20\n"));
+        assert!(csv.contains("game_over,robot_bullet,1\n"));
+        let json = report.to_json();
+        assert!(json["average_score"] == 200.0);
+        assert!(json["game_over"]["robot_bullet"] == 1);
+    }
+
+    #[test]
+    fn test_play_is_repeatable() {
+        let mut env = Environment::new(Settings::default(), Campaign::default(), Mode::Campaign);
+        let first = play(&mut env, Pilot::Random, 4, 20.0);
+        assert!(first == play(&mut env, Pilot::Random, 4, 20.0));
+        assert!(first.room_times.iter().sum::<f64>() <= 20.0 + STEP_TIME);
+        let idle = play(&mut env, Pilot::Idle, 4, 5.0);
+        assert!(idle.outcome != Outcome::Won && idle.room_times.len() == 1);
+    }
+
+    #[test]
+    fn test_replay_plays_the_same_game() {
+        let options = Options::default();
+        let mut env = Environment::new(Settings::default(), Campaign::default(), options.mode);
+        let record = play(&mut env, Pilot::Random, 6, 20.0);
+        let replay = record.replay(&options);
+        assert!(replay.seed == 6 && replay.actions.len() == record.actions.len());
+        let mut again = Environment::new(Settings::default(), Campaign::default(), replay.mode);
+        let mut last = again.reset(replay.seed);
+        for &action in &replay.actions {
+            last = again.step(action).0;
+        }
+        assert!(last.score == record.score && again.game().lives_lost() == record.lives_lost);
+    }
+
+    #[test]
+    fn test_simulated_games_are_silent() {
+        let mut env = Environment::new(Settings::default(), Campaign::default(), Mode::Campaign);
+        play(&mut env, Pilot::Random, 2, 5.0);
+        assert!(env.game().settings().audio.sound_on);
+        assert!(env.game().volume() == 0.0);
+    }
+}
@@ -0,0 +1,30 @@
+extern crate find_folder;
+extern crate opengl_graphics;
+
+use opengl_graphics::{Texture, TextureSettings};
+
+/// the images used to draw game objects
+/// textures belong to the OpenGL context of the window they were loaded in,
+/// so they are loaded again whenever the window is rebuilt
+pub struct Sprites {
+    pub player: Texture,
+    pub player_move: Texture,
+    pub heart: Texture,
+    pub enemy: Texture,
+}
+
+impl Sprites {
+    /// loads every image from the assets folder
+    pub fn load() -> Result<Self, String> {
+        let assets = find_folder::Search::ParentsThenKids(3, 3)
+            .for_folder("assets")
+            .map_err(|e| format!("could not find assets folder: {:?}", e))?;
+        let settings = TextureSettings::new();
+        Ok(Sprites {
+            player: Texture::from_path(assets.join("player.png"), &settings)?,
+            player_move: Texture::from_path(assets.join("player_move.png"), &settings)?,
+            heart: Texture::from_path(assets.join("heart.png"), &settings)?,
+            enemy: Texture::from_path(assets.join("enemy.png"), &settings)?,
+        })
+    }
+}
@@ -0,0 +1,256 @@
+//! plays the game inside a terminal, for machines with no display like over SSH
+//! the game runs exactly as in the window and is drawn with the terminal renderer
+//! the terminal is put in raw mode with `stty`, so this needs a unix like system
+
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use piston::input::Key;
+
+use game::{Game, State, FPS};
+use render::terminal::{TerminalRenderer, CELL_HEIGHT};
+
+/// frames drawn each second, lower than the update rate to keep the traffic over SSH down
+const DRAW_RATE: u64 = 30;
+/// terminals do not say when a key is let go, so a key counts as held this long after it is pressed
+/// a held key stutters if the terminal waits longer than this before repeating it,
+/// but a longer hold would walk a tapped key into walls
+const FIRST_HOLD: f64 = 0.3;
+/// and this long after each repeat once it repeats
+const REPEAT_HOLD: f64 = 0.12;
+/// seconds between checks for the terminal being resized
+const RESIZE_CHECK: f64 = 1.0;
+const HELP: &str = "ARROWS MOVE  SPACE FIRE  P PAUSE  ENTER SELECT  ESC QUIT";
+
+const LETTERS: [Key; 26] = [
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+];
+const DIGITS: [Key; 10] = [Key::D0, Key::D1, Key::D2, Key::D3, Key::D4, Key::D5, Key::D6, Key::D7, Key::D8, Key::D9];
+
+/// what a run of bytes from the terminal means
+/// Press: a key was pressed, or repeated while held
+/// Quit: escape or ctrl c
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Input {
+    Press(Key),
+    Quit,
+}
+
+/// reads the keys out of the bytes the terminal sent
+/// the arrow keys come as escape sequences, an escape on its own is the escape key
+pub fn parse_input(bytes: &[u8]) -> Vec<Input> {
+    let mut inputs = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        i += 1;
+        let key = match byte {
+            0x1b if i + 1 < bytes.len() && (bytes[i] == b'[' || bytes[i] == b'O') => {
+                i += 2;
+                match bytes[i - 1] {
+                    b'A' => Key::Up,
+                    b'B' => Key::Down,
+                    b'C' => Key::Right,
+                    b'D' => Key::Left,
+                    _ => continue,
+                }
+            },
+            0x1b | 3 => {
+                inputs.push(Input::Quit);
+                continue
+            },
+            b'\r' | b'\n' => Key::Return,
+            b'\t' => Key::Tab,
+            8 | 127 => Key::Backspace,
+            b' ' => Key::Space,
+            b',' => Key::Comma,
+            b'.' => Key::Period,
+            b'/' => Key::Slash,
+            b'-' => Key::Minus,
+            b'=' => Key::Equals,
+            b'a'..=b'z' => LETTERS[(byte - b'a') as usize],
+            b'A'..=b'Z' => LETTERS[(byte - b'A') as usize],
+            b'0'..=b'9' => DIGITS[(byte - b'0') as usize],
+            _ => continue,
+        };
+        inputs.push(Input::Press(key));
+    }
+    inputs
+}
+
+/// the keys counted as held and the time each one is let go
+#[derive(Default)]
+pub struct KeyTimer {
+    held: Vec<(Key, f64)>,
+}
+
+impl KeyTimer {
+    /// holds the key a short while when it is repeating and longer when it was just pressed
+    pub fn press(&mut self, key: Key, now: f64) {
+        match self.held.iter_mut().find(|&&mut (held, _)| held == key) {
+            Some(held) => held.1 = now + REPEAT_HOLD,
+            None => self.held.push((key, now + FIRST_HOLD)),
+        }
+    }
+
+    /// the keys whose time is up, they are no longer held
+    pub fn release(&mut self, now: f64) -> Vec<Key> {
+        let released = self.held.iter().filter(|&&(_, until)| until <= now).map(|&(key, _)| key).collect();
+        self.held.retain(|&(_, until)| until > now);
+        released
+    }
+}
+
+/// puts the terminal in raw mode on an alternate screen with the cursor hidden
+/// and puts it back how it was when dropped, even after a panic
+struct RawMode {
+    saved: String,
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(Stdio::inherit()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed, stdin has to be a terminal"))
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+impl RawMode {
+    fn enter() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l\x1b[2J");
+        io::stdout().flush()?;
+        Ok(RawMode { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[self.saved.as_str()]);
+    }
+}
+
+/// the terminal's columns and rows, 80 by 24 when it can not be asked
+fn terminal_size() -> (usize, usize) {
+    let size = stty(&["size"]).ok().and_then(|size| {
+        let mut numbers = size.split_whitespace().filter_map(|n| n.parse().ok());
+        match (numbers.next(), numbers.next()) {
+            (Some(rows), Some(cols)) => Some((cols, rows)),
+            _ => None,
+        }
+    });
+    size.unwrap_or((80, 24))
+}
+
+/// reads stdin on its own thread so the game does not wait for keys
+fn spawn_input() -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut buffer = [0; 64];
+        loop {
+            match stdin.lock().read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => if sender.send(buffer[..n].to_vec()).is_err() {
+                    break
+                },
+            }
+        }
+    });
+    receiver
+}
+
+/// plays until the player quits from the title menu or presses escape
+/// the last row of the terminal lists the keys and the rest shows the game
+pub fn run(game: &mut Game) -> io::Result<()> {
+    let _raw = RawMode::enter()?;
+    let input = spawn_input();
+    let start = Instant::now();
+    let frame = Duration::from_millis(1000 / DRAW_RATE);
+    let updates = (FPS / DRAW_RATE).max(1);
+    let mut keys = KeyTimer::default();
+    let (mut cols, mut rows) = terminal_size();
+    let mut renderer = TerminalRenderer::new(cols, rows);
+    let mut last_resize_check = 0.0;
+    let stdout = io::stdout();
+    'frames: loop {
+        let frame_start = Instant::now();
+        let elapsed = start.elapsed();
+        let now = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1e9;
+        for bytes in input.try_iter() {
+            for input in parse_input(&bytes) {
+                match input {
+                    Input::Press(key) => {
+                        keys.press(key, now);
+                        game.key(key, true);
+                    },
+                    Input::Quit => break 'frames,
+                }
+            }
+        }
+        for key in keys.release(now) {
+            game.key(key, false);
+        }
+        for _ in 0..updates {
+            game.tick(1.0 / FPS as f64);
+        }
+        if game.state() == State::Quit {
+            break
+        }
+
+        if now - last_resize_check > RESIZE_CHECK {
+            last_resize_check = now;
+            let size = terminal_size();
+            if size != (cols, rows) {
+                cols = size.0;
+                rows = size.1;
+                renderer = TerminalRenderer::new(cols, rows);
+                print!("\x1b[0m\x1b[2J");
+            }
+        }
+        game.draw(&mut renderer, [cols as f64, rows.saturating_sub(1) as f64 * CELL_HEIGHT]);
+        renderer.write(0, rows.saturating_sub(1), HELP, [0.5, 0.6, 0.7, 1.0]);
+        let mut out = stdout.lock();
+        out.write_all(renderer.frame().as_bytes())?;
+        out.flush()?;
+
+        if let Some(rest) = frame.checked_sub(frame_start.elapsed()) {
+            thread::sleep(rest);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod berzerk_test {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        assert!(parse_input(b"\x1b[A\x1b[D") == vec![Input::Press(Key::Up), Input::Press(Key::Left)]);
+        assert!(parse_input(b"pQ7 \r") == vec![
+            Input::Press(Key::P), Input::Press(Key::Q), Input::Press(Key::D7), Input::Press(Key::Space), Input::Press(Key::Return),
+        ]);
+        assert!(parse_input(b"\x1b") == vec![Input::Quit]);
+        assert!(parse_input(b"\x03") == vec![Input::Quit]);
+        assert!(parse_input(b"\x1b[5~").is_empty());
+    }
+
+    #[test]
+    fn test_keys_are_held_until_they_stop_repeating() {
+        let mut keys = KeyTimer::default();
+        keys.press(Key::Up, 0.0);
+        assert!(keys.release(0.25).is_empty());
+        keys.press(Key::Up, 0.25);
+        assert!(keys.release(0.3).is_empty());
+        assert!(keys.release(0.4) == vec![Key::Up]);
+        assert!(keys.release(2.0).is_empty());
+    }
+}
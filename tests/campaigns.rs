@@ -0,0 +1,47 @@
+extern crate berzerk;
+
+use std::fs;
+use std::path::PathBuf;
+
+use berzerk::bot::Bot;
+use berzerk::campaign::Campaign;
+use berzerk::environment::Environment;
+use berzerk::game::Mode;
+use berzerk::settings::Settings;
+
+/// every campaign shipped in assets/campaigns
+fn campaign_files() -> Vec<PathBuf> {
+    fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/campaigns")).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect()
+}
+
+#[test]
+fn test_campaigns_load() {
+    let files = campaign_files();
+    assert!(files.len() >= 2);
+    for path in &files {
+        let campaign = Campaign::load(path).unwrap();
+        assert!(!campaign.name.is_empty());
+    }
+}
+
+#[test]
+fn test_smoke_every_campaign() {
+    for path in campaign_files() {
+        let campaign = Campaign::load(&path).unwrap();
+        for &mode in [Mode::Campaign, Mode::Endless].iter() {
+            let mut env = Environment::new(Settings::default(), campaign.clone(), mode);
+            let mut bot = Bot::new();
+            let mut observation = env.reset(11);
+            for _ in 0..3000 {
+                let (next, _, done) = env.step(bot.act(&observation));
+                observation = next;
+                if done {
+                    break
+                }
+            }
+            assert!(observation.players[0].health <= observation.players[0].max_health);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+extern crate berzerk;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use berzerk::campaign::Campaign;
+use berzerk::game::{Direction, Game, Mode, LOGICAL_SIZE};
+use berzerk::highscores::HighScores;
+use berzerk::models::enemy::EnemyKind;
+use berzerk::net::{BulletState, EnemyState, PlayerState, Screen, Snapshot};
+use berzerk::render::{software, Image, SpriteImages};
+use berzerk::settings::Settings;
+
+/// how far apart a channel of the same pixel can be before the pixel counts as changed
+const CHANNEL_TOLERANCE: u8 = 8;
+/// how many changed pixels are let through, so rounding differences on other machines do not fail the test
+const CHANGED_PIXELS_ALLOWED: usize = 20;
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn sprites() -> SpriteImages {
+    SpriteImages::load_from(&manifest_dir().join("assets")).unwrap()
+}
+
+fn render(game: &mut Game) -> Image {
+    software::render(game, &sprites(), LOGICAL_SIZE[0] as u32, LOGICAL_SIZE[1] as u32)
+}
+
+fn player(x: f64, y: f64, health: u32, score: u32, kills: u32) -> PlayerState {
+    PlayerState { x, y, dir: Direction::EAST, health, max_health: 3, is_moving: false, score, kills }
+}
+
+fn scene(screen: Screen, mode: Mode, level: u32, score: u32, players: Vec<PlayerState>) -> Snapshot {
+    Snapshot {
+        tick: 0,
+        ack: 0,
+        screen,
+        mode,
+        time_left: None,
+        level,
+        score,
+        players,
+        enemies: Vec::new(),
+        bullets: Vec::new(),
+    }
+}
+
+fn staged(snapshot: &Snapshot) -> Game {
+    let mut game = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+    game.stage(snapshot);
+    game
+}
+
+/// the pixels that differ by more than the tolerance drawn red over a faded copy of the reference
+fn diff(reference: &Image, actual: &Image) -> (usize, Image) {
+    let mut changed = 0;
+    let mut image = Image::new(reference.width, reference.height);
+    for (i, (old, new)) in reference.pixels.chunks(4).zip(actual.pixels.chunks(4)).enumerate() {
+        let far = old.iter().zip(new).any(|(&a, &b)| (a as i32 - b as i32).abs() > CHANNEL_TOLERANCE as i32);
+        let pixel = if far {
+            changed += 1;
+            [255, 0, 0, 255]
+        } else {
+            let grey = ((old[0] as u32 + old[1] as u32 + old[2] as u32) / 9) as u8;
+            [grey, grey, grey, 255]
+        };
+        image.pixels[i * 4..i * 4 + 4].copy_from_slice(&pixel);
+    }
+    (changed, image)
+}
+
+/// compares the image with tests/snapshots/NAME.png
+/// with BERZERK_UPDATE_SNAPSHOTS set the reference is written instead, for when the look changed on purpose
+/// on a failure the image drawn and the diff are saved under target/snapshots
+fn assert_snapshot(name: &str, actual: &Image) {
+    let reference_path = manifest_dir().join("tests").join("snapshots").join(format!("{}.png", name));
+    if env::var_os("BERZERK_UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        actual.save(&reference_path).unwrap();
+        return
+    }
+    let reference = Image::load(&reference_path)
+        .unwrap_or_else(|e| panic!("{}, run with BERZERK_UPDATE_SNAPSHOTS=1 to make it", e));
+    let failures = manifest_dir().join("target").join("snapshots");
+    let fail = |message: String, diff: Option<Image>| {
+        fs::create_dir_all(&failures).unwrap();
+        let actual_path = failures.join(format!("{}.actual.png", name));
+        actual.save(&actual_path).unwrap();
+        match diff {
+            Some(diff) => {
+                let diff_path = failures.join(format!("{}.diff.png", name));
+                diff.save(&diff_path).unwrap();
+                panic!("{}: {}, drawn {}, diff {}", name, message, actual_path.display(), diff_path.display());
+            },
+            None => panic!("{}: {}, drawn {}", name, message, actual_path.display()),
+        }
+    };
+    if (reference.width, reference.height) != (actual.width, actual.height) {
+        fail(format!("drawn {}x{} but the reference is {}x{}", actual.width, actual.height, reference.width, reference.height), None);
+    }
+    let (changed, image) = diff(&reference, actual);
+    if changed > CHANGED_PIXELS_ALLOWED {
+        fail(format!("{} pixels changed, {} are allowed", changed, CHANGED_PIXELS_ALLOWED), Some(image));
+    }
+}
+
+#[test]
+fn test_snapshot_title() {
+    let mut game = Game::new(Settings::default(), HighScores::default(), Campaign::default());
+    assert_snapshot("title", &render(&mut game));
+}
+
+#[test]
+fn test_snapshot_room() {
+    let mut snapshot = scene(Screen::Playing, Mode::Campaign, 2, 1250, vec![player(200.0, 250.0, 2, 1250, 5)]);
+    snapshot.players[0].is_moving = true;
+    snapshot.enemies = vec![
+        EnemyState { x: 520.0, y: 120.0, dir: Direction::SOUTH, kind: EnemyKind::Drone },
+        EnemyState { x: 620.0, y: 380.0, dir: Direction::WEST, kind: EnemyKind::Soldier },
+        EnemyState { x: 760.0, y: 200.0, dir: Direction::NORTH, kind: EnemyKind::Hunter },
+    ];
+    snapshot.bullets = vec![
+        BulletState { owner: Some(0), x: 320.0, y: 300.0, dir: Direction::EAST, speed: 5.0 },
+        BulletState { owner: None, x: 560.0, y: 380.0, dir: Direction::WEST, speed: 5.0 },
+    ];
+    let mut game = staged(&snapshot);
+    assert_snapshot("room", &render(&mut game));
+}
+
+#[test]
+fn test_snapshot_two_players() {
+    let players = vec![player(120.0, 300.0, 3, 300, 3), player(120.0, 345.0, 1, 180, 2)];
+    let mut snapshot = scene(Screen::Playing, Mode::Endless, 7, 480, players);
+    snapshot.enemies = vec![EnemyState { x: 700.0, y: 420.0, dir: Direction::WEST, kind: EnemyKind::Soldier }];
+    let mut game = staged(&snapshot);
+    assert_snapshot("two_players", &render(&mut game));
+}
+
+#[test]
+fn test_snapshot_game_over() {
+    let snapshot = scene(Screen::GameOver, Mode::Campaign, 4, 90, vec![player(400.0, 300.0, 0, 90, 1)]);
+    let mut game = staged(&snapshot);
+    assert_snapshot("game_over", &render(&mut game));
+}
+
+#[test]
+fn test_snapshot_versus_won() {
+    let players = vec![player(100.0, 300.0, 1, 0, 3), player(800.0, 300.0, 0, 0, 1)];
+    let mut game = staged(&scene(Screen::Won, Mode::Versus, 1, 0, players));
+    assert_snapshot("versus_won", &render(&mut game));
+}
+
+#[test]
+fn test_diff_marks_changed_pixels() {
+    let mut reference = Image::new(4, 1);
+    reference.pixels = vec![10; 16];
+    let mut actual = reference.clone();
+    actual.pixels[0] = 10 + CHANNEL_TOLERANCE;
+    actual.pixels[4] = 11 + CHANNEL_TOLERANCE;
+    let (changed, image) = diff(&reference, &actual);
+    assert!(changed == 1);
+    assert!(image.pixel(1, 0) == [255, 0, 0, 255] && image.pixel(0, 0) != [255, 0, 0, 255]);
+}
@@ -0,0 +1,44 @@
+extern crate berzerk;
+
+use berzerk::campaign::Campaign;
+use berzerk::environment::{Action, Environment};
+use berzerk::game::{Direction, Mode};
+use berzerk::simulation::{self, Options, Outcome, Pilot, Report};
+use berzerk::settings::Settings;
+
+#[test]
+fn test_environment_from_outside() {
+    let mut env = Environment::new(Settings::default(), Campaign::default(), Mode::Endless);
+    let start = env.reset(21);
+    assert!(start.players.len() == 1 && start.level == 1);
+    let (after, _, done) = env.step(Action { dir: Some(Direction::EAST), fire: true });
+    assert!(!done);
+    assert!(after.players[0].x > start.players[0].x);
+}
+
+#[test]
+fn test_batch_is_repeatable() {
+    let options = Options { games: 5, max_time: 120.0, ..Options::default() };
+    let batch = || {
+        let mut records = Vec::new();
+        simulation::run(&options, Campaign::default(), |record| records.push(record.clone()));
+        records
+    };
+    let records = batch();
+    assert!(records.len() == 5);
+    assert!(records.iter().map(|record| record.seed).collect::<Vec<_>>() == vec![1, 2, 3, 4, 5]);
+    assert!(records == batch());
+
+    let mut report = Report::default();
+    for record in &records {
+        report.add(record);
+    }
+    assert!(report.won + report.lost + report.unfinished == 5);
+    assert!(report.levels[0].reached == 5);
+}
+
+#[test]
+fn test_idle_pilot_never_wins() {
+    let options = Options { pilot: Pilot::Idle, games: 3, max_time: 30.0, ..Options::default() };
+    simulation::run(&options, Campaign::default(), |record| assert!(record.outcome != Outcome::Won));
+}
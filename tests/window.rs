@@ -0,0 +1,26 @@
+//! opens a real window, which only works on the main thread, so this runs without the test harness
+//! and is skipped on machines with no display to open it on
+
+extern crate glutin_window;
+extern crate opengl_graphics;
+extern crate piston;
+
+use glutin_window::GlutinWindow as Window;
+use opengl_graphics::{GlGraphics, OpenGL};
+use piston::window::WindowSettings;
+use std::env;
+
+fn main() {
+    if env::var_os("DISPLAY").is_none() && env::var_os("WAYLAND_DISPLAY").is_none() {
+        println!("test_window_creation skipped, there is no display");
+        return
+    }
+    let opengl = OpenGL::V3_2;
+    let _window: Window = WindowSettings::new("create test", [500, 500])
+        .graphics_api(opengl)
+        .exit_on_esc(true)
+        .build()
+        .expect("Error creating window");
+    let _gl = GlGraphics::new(opengl);
+    println!("test_window_creation ok");
+}